@@ -0,0 +1,95 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, punctuated::Punctuated, Data, DeriveInput, Field, Fields, Lit, Meta, NestedMeta, Token};
+
+/// Derives `debris::FromHtml` for a struct with named fields, extracting each field from the node according to its `#[debris(...)]`
+/// attribute:
+///
+/// - `select = "..."` first finds the single descendant matching the selector, like `Find::find`. Without it, the field is
+///   extracted from the node itself.
+/// - `text` takes the node's text, `attr = "..."` the value of an attribute. Without either, the field's type is extracted with its
+///   own `FromHtml` impl, which allows nesting structs.
+/// - `parse` parses the text or attribute into the field's type with `Text::parse`. Without it, the field must be a `String`.
+///
+/// Errors carry the usual operation breadcrumb, so a failing field shows up as the selector it was extracted with.
+#[proc_macro_derive(FromHtml, attributes(debris))]
+pub fn derive_from_html(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	match expand(&input) {
+		Ok(tokens) => tokens.into(),
+		Err(error) => error.to_compile_error().into(),
+	}
+}
+
+struct Spec {
+	select: Option<String>,
+	value: Value,
+	parse: bool,
+}
+
+enum Value {
+	Nested,
+	Text,
+	Attr(String),
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+	let fields = match &input.data {
+		Data::Struct(data) => match &data.fields {
+			Fields::Named(fields) => &fields.named,
+			_ => return Err(syn::Error::new_spanned(&input.ident, "FromHtml can only be derived for structs with named fields")),
+		},
+		_ => return Err(syn::Error::new_spanned(&input.ident, "FromHtml can only be derived for structs with named fields")),
+	};
+	let mut inits = Vec::new();
+	for field in fields {
+		let name = field.ident.as_ref().unwrap();
+		let ty = &field.ty;
+		let spec = parse_spec(field)?;
+		let find = match &spec.select {
+			Some(selector) => quote! { let node = ::debris::Find::find(node, #selector)?; },
+			None => quote! {},
+		};
+		let value = match (spec.value, spec.parse) {
+			(Value::Nested, false) => quote! { <#ty as ::debris::FromHtml>::from_node(&node)? },
+			(Value::Nested, true) => return Err(syn::Error::new_spanned(field, "`parse` needs `text` or `attr`")),
+			(Value::Text, false) => quote! { node.text().string() },
+			(Value::Text, true) => quote! { node.text().parse::<#ty>()? },
+			(Value::Attr(key), false) => quote! { node.attr(#key)?.string() },
+			(Value::Attr(key), true) => quote! { node.attr(#key)?.parse::<#ty>()? },
+		};
+		inits.push(quote! { #name: { #find #value } });
+	}
+	let ident = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	Ok(quote! {
+		impl #impl_generics ::debris::FromHtml for #ident #ty_generics #where_clause {
+			fn from_node(node: &::debris::Node) -> ::debris::Result<Self> {
+				Ok(Self { #(#inits,)* })
+			}
+		}
+	})
+}
+
+fn parse_spec(field: &Field) -> syn::Result<Spec> {
+	let mut spec = Spec { select: None, value: Value::Nested, parse: false };
+	for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("debris")) {
+		for meta in attr.parse_args_with(Punctuated::<NestedMeta, Token![,]>::parse_terminated)? {
+			match meta {
+				NestedMeta::Meta(Meta::NameValue(pair)) if pair.path.is_ident("select") => spec.select = Some(lit_str(&pair.lit)?),
+				NestedMeta::Meta(Meta::NameValue(pair)) if pair.path.is_ident("attr") => spec.value = Value::Attr(lit_str(&pair.lit)?),
+				NestedMeta::Meta(Meta::Path(path)) if path.is_ident("text") => spec.value = Value::Text,
+				NestedMeta::Meta(Meta::Path(path)) if path.is_ident("parse") => spec.parse = true,
+				other => return Err(syn::Error::new_spanned(other, "expected `select = \"...\"`, `text`, `attr = \"...\"` or `parse`")),
+			}
+		}
+	}
+	Ok(spec)
+}
+
+fn lit_str(lit: &Lit) -> syn::Result<String> {
+	match lit {
+		Lit::Str(lit) => Ok(lit.value()),
+		_ => Err(syn::Error::new_spanned(lit, "expected a string literal")),
+	}
+}
@@ -1,4 +1,4 @@
-use std::{cell::RefCell, collections::HashMap, hash::Hash};
+use std::{borrow::Borrow, cell::RefCell, collections::HashMap, hash::Hash};
 
 pub struct ArenaCache<K: Hash+Eq, V> {
 	entries: RefCell<HashMap<K, Box<V>>>,
@@ -9,14 +9,18 @@ impl<K: Hash+Eq, V> ArenaCache<K, V> {
 		ArenaCache { entries: RefCell::new(HashMap::new()) }
 	}
 
-	pub fn query<'a>(&'a self, key: K, computation: impl FnOnce(&K) -> V) -> &'a V {
-		if !self.entries.borrow().contains_key(&key) {
-			let value = Box::new(computation(&key));
+	pub fn query<'a, Q>(&'a self, key: &Q, computation: impl FnOnce(&Q) -> V) -> &'a V
+	where
+		K: Borrow<Q>,
+		Q: Hash+Eq+ToOwned<Owned = K>+?Sized,
+	{
+		if !self.entries.borrow().contains_key(key) {
+			let value = Box::new(computation(key));
 			let value_ref = unsafe { (&*value as *const V).as_ref() }.unwrap();
-			self.entries.borrow_mut().insert(key, value);
+			self.entries.borrow_mut().insert(key.to_owned(), value);
 			value_ref
 		} else {
-			unsafe { (self.entries.borrow()[&key].as_ref() as *const V).as_ref() }.unwrap()
+			unsafe { (self.entries.borrow()[key].as_ref() as *const V).as_ref() }.unwrap()
 		}
 	}
 }
@@ -0,0 +1,51 @@
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
+
+/// Picks the encoding of an HTML page like browsers do: a byte order mark wins, then the `charset` of the `Content-Type` header,
+/// then a `<meta charset>` or `<meta http-equiv="content-type">` within the first 1024 bytes. Pages without any declaration are
+/// read as UTF-8 if they're valid UTF-8, and as windows-1252 otherwise.
+pub(crate) fn detect(bytes: &[u8], content_type: Option<&str>) -> &'static Encoding {
+	if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+		return encoding;
+	}
+	if let Some(encoding) = content_type.and_then(|content_type| charset_param(content_type.as_bytes())) {
+		return encoding;
+	}
+	if let Some(encoding) = prescan(&bytes[..bytes.len().min(1024)]) {
+		// A page can't be UTF-16 if its ASCII `<meta>` was readable, so the declaration is wrong.
+		return if encoding.name().starts_with("UTF-16") { UTF_8 } else { encoding };
+	}
+	if std::str::from_utf8(bytes).is_ok() { UTF_8 } else { WINDOWS_1252 }
+}
+
+/// Looks for `charset=` inside `<meta>` tags. Comments and other tags aren't skipped precisely, which is enough for real pages.
+fn prescan(head: &[u8]) -> Option<&'static Encoding> {
+	let head = head.to_ascii_lowercase();
+	let mut rest = head.as_slice();
+	while let Some(start) = find(rest, b"<meta") {
+		let tag = &rest[start..];
+		let tag = &tag[..find(tag, b">").unwrap_or(tag.len())];
+		if let Some(encoding) = charset_param(tag) {
+			return Some(encoding);
+		}
+		rest = &rest[start + tag.len()..];
+	}
+	None
+}
+
+/// Value of a `charset=` parameter, quoted or not, as in `text/html; charset=utf-8` or `<meta charset="utf-8">`.
+fn charset_param(text: &[u8]) -> Option<&'static Encoding> {
+	let text = text.to_ascii_lowercase();
+	let start = find(&text, b"charset")? + b"charset".len();
+	let value = trim_start(trim_start(&text[start..]).strip_prefix(b"=")?);
+	let value = value.strip_prefix(b"\"").or_else(|| value.strip_prefix(b"'")).unwrap_or(value);
+	let end = value.iter().position(|c| matches!(c, b'"' | b'\'' | b';' | b'>' | b'/') || c.is_ascii_whitespace()).unwrap_or(value.len());
+	Encoding::for_label(&value[..end])
+}
+
+fn trim_start(text: &[u8]) -> &[u8] {
+	&text[text.iter().position(|c| !c.is_ascii_whitespace()).unwrap_or(text.len())..]
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+	haystack.windows(needle.len()).position(|window| window == needle)
+}
@@ -0,0 +1,168 @@
+use crate::{Context, Document, Operation, Reason, Result};
+use std::{fmt, sync::Arc};
+
+/// Configures how a [`Document`] is parsed and how it reports text and errors. [`Document::new`] is the same as parsing with the
+/// default settings.
+#[derive(Clone, Debug, Default)]
+pub struct DocumentBuilder {
+	strict: bool,
+	trim_policy: TrimPolicy,
+	snapshot_policy: SnapshotPolicy,
+	redacted_attrs: Vec<String>,
+	capture_backtrace: Option<bool>,
+}
+
+/// What happens to whitespace around and inside extracted text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TrimPolicy {
+	/// Remove leading and trailing whitespace.
+	#[default]
+	Trim,
+	/// Also collapse whitespace runs inside the text to a single space. Multiline text keeps its line breaks.
+	Collapse,
+	/// Leave the text exactly as it is in the HTML.
+	Keep,
+}
+
+/// How the HTML captured in [`Error::snapshots`](crate::Error::snapshots) is cleaned up and shortened, set with
+/// [`Document::set_snapshot_policy`] or [`DocumentBuilder::snapshot_policy`]. Snapshots are first passed through the redaction
+/// callback, then truncated.
+#[derive(Clone, Default)]
+pub struct SnapshotPolicy {
+	max_length: Option<usize>,
+	truncation: Truncation,
+	redact: Option<Redact>,
+}
+type Redact = Arc<dyn Fn(&str) -> String+Send+Sync>;
+
+/// Which part of a snapshot over the length limit is kept.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Truncation {
+	#[default]
+	KeepStart,
+	KeepEnd,
+	/// Keep both ends, replacing the middle with a comment saying how much was left out.
+	KeepBoth,
+}
+
+impl SnapshotPolicy {
+	pub fn new() -> SnapshotPolicy {
+		SnapshotPolicy::default()
+	}
+
+	/// Maximum length of each snapshot in characters, not counting the marker added by [`Truncation::KeepBoth`].
+	pub fn max_length(mut self, max_length: usize) -> SnapshotPolicy {
+		self.max_length = Some(max_length);
+		self
+	}
+
+	pub fn truncation(mut self, truncation: Truncation) -> SnapshotPolicy {
+		self.truncation = truncation;
+		self
+	}
+
+	/// Rewrites each snapshot before it's stored, e.g. to mask session tokens that aren't in attributes covered by
+	/// [`DocumentBuilder::redact_attrs`]. It runs on the whole snapshot, before truncation.
+	pub fn redact(mut self, redact: impl Fn(&str) -> String+Send+Sync+'static) -> SnapshotPolicy {
+		self.redact = Some(Arc::new(redact));
+		self
+	}
+
+	pub(crate) fn apply(&self, snapshot: String) -> String {
+		let snapshot = match &self.redact {
+			Some(redact) => redact(&snapshot),
+			None => snapshot,
+		};
+		let limit = match self.max_length {
+			Some(limit) => limit,
+			None => return snapshot,
+		};
+		let length = snapshot.chars().count();
+		if length <= limit {
+			return snapshot;
+		}
+		match self.truncation {
+			Truncation::KeepStart => snapshot.chars().take(limit).collect(),
+			Truncation::KeepEnd => snapshot.chars().skip(length - limit).collect(),
+			Truncation::KeepBoth => {
+				let head: String = snapshot.chars().take(limit - limit / 2).collect();
+				let tail: String = snapshot.chars().skip(length - limit / 2).collect();
+				format!("{}<!-- {} characters omitted -->{}", head, length - limit, tail)
+			},
+		}
+	}
+}
+
+impl fmt::Debug for SnapshotPolicy {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("SnapshotPolicy")
+			.field("max_length", &self.max_length)
+			.field("truncation", &self.truncation)
+			.field("redact", &self.redact.as_ref().map(|_| ".."))
+			.finish()
+	}
+}
+
+impl DocumentBuilder {
+	pub fn new() -> DocumentBuilder {
+		DocumentBuilder::default()
+	}
+
+	/// Fail if the HTML parser had to recover from any errors, instead of silently fixing the markup up.
+	pub fn strict(mut self, strict: bool) -> DocumentBuilder {
+		self.strict = strict;
+		self
+	}
+
+	pub fn trim_policy(mut self, trim_policy: TrimPolicy) -> DocumentBuilder {
+		self.trim_policy = trim_policy;
+		self
+	}
+
+	/// Maximum length of each snapshot captured in errors, in characters. Shorthand for a [`SnapshotPolicy`] with only
+	/// [`SnapshotPolicy::max_length`] set, which replaces any policy set before.
+	pub fn snapshot_limit(mut self, snapshot_limit: usize) -> DocumentBuilder {
+		self.snapshot_policy = SnapshotPolicy::new().max_length(snapshot_limit);
+		self
+	}
+
+	pub fn snapshot_policy(mut self, snapshot_policy: SnapshotPolicy) -> DocumentBuilder {
+		self.snapshot_policy = snapshot_policy;
+		self
+	}
+
+	/// Attributes whose values are masked in error snapshots. Takes the same patterns as
+	/// [`Node::outer_html_redacted`](crate::Node::outer_html_redacted).
+	pub fn redact_attrs(mut self, attrs: &[&str]) -> DocumentBuilder {
+		self.redacted_attrs = attrs.iter().map(|attr| (*attr).to_owned()).collect();
+		self
+	}
+
+	/// See [`Document::set_capture_backtrace`].
+	pub fn capture_backtrace(mut self, capture_backtrace: bool) -> DocumentBuilder {
+		self.capture_backtrace = Some(capture_backtrace);
+		self
+	}
+
+	/// Decodes the page like [`Document::from_bytes`] and parses it with these settings.
+	#[cfg(feature = "encoding")]
+	pub fn parse_bytes(self, bytes: &[u8], content_type: Option<&str>) -> Result<Document> {
+		let (html, _, _) = crate::charset::detect(bytes, content_type).decode(bytes);
+		self.parse(&html)
+	}
+
+	pub fn parse(self, html: &str) -> Result<Document> {
+		let mut document = Document::new(html);
+		document.trim_policy = self.trim_policy;
+		document.snapshot_policy = self.snapshot_policy;
+		document.redacted_attrs = self.redacted_attrs;
+		if let Some(capture_backtrace) = self.capture_backtrace {
+			document.capture_backtrace = capture_backtrace;
+		}
+		if self.strict && !document.tree.errors.is_empty() {
+			let errors = document.parse_errors().map(str::to_owned).collect();
+			return Err(document.make_error(Reason::InvalidHtml(errors), Operation::ParseHtml));
+		}
+		Ok(document)
+	}
+}
@@ -0,0 +1,48 @@
+use crate::Error;
+use std::{collections::hash_map::DefaultHasher, hash::{Hash, Hasher}};
+
+/// Plain data version of an [`Error`] for logging pipelines, created with [`Error::to_record`]. With the `json` feature it can be
+/// serialized with serde, or directly with [`Error::to_json`]. Snapshots are summarized rather than included, so records stay
+/// small and pages with personal data don't end up in logs.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct ErrorRecord {
+	/// The whole error message, as printed by `Display`.
+	pub message: String,
+	pub reason: String,
+	/// The [`Error::operation_names`], which are stable enough to group errors by.
+	pub operations: Vec<String>,
+	pub snapshots: Vec<SnapshotSummary>,
+	/// One entry per line of the backtrace's `Debug` output, empty if it wasn't captured.
+	pub backtrace: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct SnapshotSummary {
+	/// Hex digest of the snapshot, for telling whether two failures saw the same HTML. It's not cryptographic, and can change
+	/// between Rust versions.
+	pub hash: String,
+	/// Length in bytes.
+	pub length: usize,
+}
+
+impl ErrorRecord {
+	pub(crate) fn new(error: &Error) -> ErrorRecord {
+		ErrorRecord {
+			message: error.to_string(),
+			reason: error.reason.to_string(),
+			operations: error.operation_names(),
+			snapshots: error
+				.snapshots
+				.iter()
+				.map(|snapshot| {
+					let mut hasher = DefaultHasher::new();
+					snapshot.hash(&mut hasher);
+					SnapshotSummary { hash: format!("{:016x}", hasher.finish()), length: snapshot.len() }
+				})
+				.collect(),
+			backtrace: error.backtrace.as_ref().map_or_else(Vec::new, |backtrace| format!("{:?}", backtrace).lines().map(str::to_owned).collect()),
+		}
+	}
+}
@@ -0,0 +1,37 @@
+use crate::Error;
+use std::collections::HashMap;
+
+/// Collects errors from a batch of scrapes and counts them by where they happened, so a run over many pages can be summarized as
+/// e.g. "`find:.votes`, `text`, `parse` failed 37 times" instead of stopping at the first failure.
+#[derive(Debug, Default)]
+pub struct ErrorReport {
+	groups: Vec<(Vec<String>, usize)>,
+	positions: HashMap<Vec<String>, usize>,
+}
+
+impl ErrorReport {
+	pub fn new() -> ErrorReport {
+		ErrorReport::default()
+	}
+
+	/// Counts the error under its [`Error::operation_names`]. The error itself is dropped.
+	pub fn push(&mut self, error: Error) {
+		let breadcrumb = error.operation_names();
+		match self.positions.get(&breadcrumb) {
+			Some(&position) => self.groups[position].1 += 1,
+			None => {
+				self.positions.insert(breadcrumb.clone(), self.groups.len());
+				self.groups.push((breadcrumb, 1));
+			},
+		}
+	}
+
+	/// Distinct breadcrumbs with the number of errors for each, most frequent first. Ties keep the order the breadcrumbs were first
+	/// seen in.
+	pub fn summarize(&self) -> Vec<(Vec<&str>, usize)> {
+		let mut summary: Vec<_> =
+			self.groups.iter().map(|(breadcrumb, count)| (breadcrumb.iter().map(String::as_str).collect::<Vec<_>>(), *count)).collect();
+		summary.sort_by(|(_, a), (_, b)| b.cmp(a));
+		summary
+	}
+}
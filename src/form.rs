@@ -0,0 +1,141 @@
+use crate::{Context, Node, Operation, Reason, Result, Text};
+use scraper::ElementRef;
+use std::fmt::Write;
+
+/// Controls of a `<form>`, created with [`Node::as_form`]. Values start out as the defaults in the HTML, which is what's needed to
+/// pick up hidden fields like CSRF tokens, and can be changed with [`Form::set`] before serializing the form for submission.
+pub struct Form<'a> {
+	node: &'a Node<'a>,
+	fields: Vec<FormField>,
+}
+
+/// A named control of a form. Each selected option of a `<select multiple>` is a separate field.
+#[derive(Clone, Debug)]
+pub struct FormField {
+	pub name: String,
+	/// The `type` of an `<input>` in lowercase, like `"hidden"` or `"checkbox"`, or the tag name of other controls.
+	pub kind: String,
+	pub value: String,
+	/// Whether the field is sent when the form is submitted without clicking any particular button. This is false for disabled
+	/// controls, unchecked checkboxes and radio buttons, buttons, and file inputs.
+	pub submitted: bool,
+}
+
+impl<'a> Form<'a> {
+	pub(crate) fn new(node: &'a Node<'a>) -> Result<Form<'a>> {
+		if node.element.value().name() != "form" {
+			return Err(node.make_error(Reason::Logic("expected a <form>"), Operation::Form));
+		}
+		let mut fields = Vec::new();
+		for control in node.element.descendants().filter_map(ElementRef::wrap) {
+			let element = control.value();
+			let name = match element.attr("name") {
+				Some(name) if !name.is_empty() => name.to_owned(),
+				_ => continue,
+			};
+			let enabled = element.attr("disabled").is_none();
+			match element.name() {
+				"input" => {
+					let kind = element.attr("type").unwrap_or("text").to_ascii_lowercase();
+					let checkable = kind == "checkbox" || kind == "radio";
+					let value = element.attr("value").unwrap_or(if checkable { "on" } else { "" }).to_owned();
+					let submitted = enabled
+						&& match kind.as_str() {
+							"checkbox" | "radio" => element.attr("checked").is_some(),
+							"submit" | "image" | "button" | "reset" | "file" => false,
+							_ => true,
+						};
+					fields.push(FormField { name, kind, value, submitted });
+				},
+				"select" => {
+					let options: Vec<ElementRef> =
+						control.descendants().filter_map(ElementRef::wrap).filter(|option| option.value().name() == "option").collect();
+					let mut selected: Vec<ElementRef> = options.iter().copied().filter(|option| option.value().attr("selected").is_some()).collect();
+					if selected.is_empty() && element.attr("multiple").is_none() {
+						selected.extend(options.first());
+					}
+					for option in selected {
+						let value = match option.value().attr("value") {
+							Some(value) => value.to_owned(),
+							None => option.text().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" "),
+						};
+						fields.push(FormField { name: name.clone(), kind: "select".to_owned(), value, submitted: enabled });
+					}
+				},
+				"textarea" => fields.push(FormField { name, kind: "textarea".to_owned(), value: control.text().collect(), submitted: enabled }),
+				"button" => {
+					let value = element.attr("value").unwrap_or("").to_owned();
+					fields.push(FormField { name, kind: "button".to_owned(), value, submitted: false });
+				},
+				_ => (),
+			}
+		}
+		Ok(Form { node, fields })
+	}
+
+	pub fn fields(&self) -> &[FormField] {
+		&self.fields
+	}
+
+	/// The `action` attribute as written, without resolving it against the page URL.
+	pub fn action(&self) -> Option<&'a str> {
+		self.node.element.value().attr("action")
+	}
+
+	/// The `method` attribute in lowercase, `"get"` if it's missing.
+	pub fn method(&self) -> String {
+		self.node.element.value().attr("method").map_or_else(|| "get".to_owned(), str::to_ascii_lowercase)
+	}
+
+	/// Value of the first submitted field called `name`.
+	pub fn value(&self, name: &str) -> Result<Text<'a>> {
+		let operation = Operation::FormField { name: name.to_owned() };
+		match self.fields.iter().find(|field| field.submitted && field.name == name) {
+			Some(field) => Ok(Text {
+				document: self.node.document,
+				source: Some(self.node),
+				detached: Vec::new(),
+				operation,
+				value: field.value.clone(),
+			}),
+			None => Err(self.node.make_error(Reason::NotFound, operation)),
+		}
+	}
+
+	/// Makes `value` the only value submitted for `name`. The first field called `name` takes the value, and any others, like the
+	/// rest of a radio group, stop being submitted. If there's no such field, a hidden one is added.
+	pub fn set(&mut self, name: &str, value: &str) {
+		let mut fields = self.fields.iter_mut().filter(|field| field.name == name);
+		match fields.next() {
+			Some(field) => {
+				field.value = value.to_owned();
+				field.submitted = true;
+				fields.for_each(|field| field.submitted = false);
+			},
+			None => self.fields.push(FormField { name: name.to_owned(), kind: "hidden".to_owned(), value: value.to_owned(), submitted: true }),
+		}
+	}
+
+	/// Names and values of the submitted fields, in document order.
+	pub fn pairs(&self) -> Vec<(&str, &str)> {
+		self.fields.iter().filter(|field| field.submitted).map(|field| (field.name.as_str(), field.value.as_str())).collect()
+	}
+
+	/// The submitted fields encoded as `application/x-www-form-urlencoded`, ready to be used as a request body or query string.
+	pub fn urlencoded(&self) -> String {
+		let pairs: Vec<String> = self.pairs().into_iter().map(|(name, value)| format!("{}={}", urlencode(name), urlencode(value))).collect();
+		pairs.join("&")
+	}
+}
+
+fn urlencode(text: &str) -> String {
+	let mut encoded = String::new();
+	for byte in text.bytes() {
+		match byte {
+			b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'*' | b'-' | b'.' | b'_' => encoded.push(byte as char),
+			b' ' => encoded.push('+'),
+			byte => write!(encoded, "%{:02X}", byte).unwrap(),
+		}
+	}
+	encoded
+}
@@ -1,22 +1,77 @@
 use wasm_backtrace::Backtrace;
 use scraper::{ElementRef, Selector};
-use std::{fmt, str::FromStr};
+use text_selector::CompiledSelector;
+use std::{
+	any::{type_name, Any},
+	borrow::Cow,
+	collections::HashMap,
+	fmt,
+	hash::Hash,
+	io,
+	num::{IntErrorKind, ParseIntError},
+	path::{Path, PathBuf},
+	str::FromStr,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc, OnceLock,
+	},
+};
 
 mod arena_cache;
+#[cfg(feature = "encoding")]
+mod charset;
+mod document_builder;
+mod error_record;
+mod error_report;
+mod form;
+mod metadata;
+mod microdata;
+mod node_owned;
+mod number_format;
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+mod report;
+mod selector_builder;
+mod table;
+mod text_options;
+mod text_selector;
+mod xpath;
 
-#[derive(Debug)]
+#[cfg(feature = "derive")]
+pub use debris_derive::FromHtml;
+pub use document_builder::{DocumentBuilder, SnapshotPolicy, TrimPolicy, Truncation};
+pub use error_record::{ErrorRecord, SnapshotSummary};
+pub use error_report::ErrorReport;
+pub use form::{Form, FormField};
+pub use metadata::Metadata;
+pub use microdata::{Item, ItemValue};
+pub use node_owned::NodeOwned;
+pub use number_format::NumberFormat;
+pub use selector_builder::SelectorBuilder;
+pub use table::Table;
+pub use text_options::TextOptions;
+
+/// Pretty-printing with `{:#?}` shows only the innermost snapshot, trimmed to 2000 characters or to the precision if one is given,
+/// e.g. `{:#.500?}`. The compact `{:?}` prints every snapshot in full.
 pub struct Error {
 	pub reason: Reason,
 	pub operations: Vec<Operation>,
 	pub snapshots: Vec<String>,
-	pub backtrace: Backtrace,
+	/// Where the error was created, unless backtraces are turned off with [`Document::set_capture_backtrace`].
+	pub backtrace: Option<Backtrace>,
 }
 pub type Result<T> = std::result::Result<T, Error>;
 
 pub trait Find: Context {
-	fn find_all(&self, selector: &'static str) -> Collection;
-	fn find(&self, selector: &'static str) -> Result<Node> {
-		let mut iter = self.find_all(selector).iterator;
+	/// Fails only if the selector is invalid. All methods taking selectors report that with [`Reason::InvalidSelector`] instead of
+	/// panicking.
+	///
+	/// Besides CSS, selectors can filter by text with `:contains("...")`, matching elements whose text contains the string, and
+	/// `:text-is("...")`, matching elements whose trimmed text with collapsed whitespace is exactly the string.
+	fn find_all(&self, selector: impl Into<Cow<'static, str>>) -> Result<Collection>;
+	fn find(&self, selector: impl Into<Cow<'static, str>>) -> Result<Node> {
+		let selector = selector.into();
+		self.get_document().compile_selector(&selector).map_err(|reason| self.make_error(reason, Operation::Find { selector: selector.clone() }))?;
+		let mut iter = self.find_all(selector.clone())?.iterator;
 		let element = iter.next();
 		let is_only = iter.next().is_none();
 		match element {
@@ -27,22 +82,160 @@ pub trait Find: Context {
 			None => Err(self.make_error(Reason::NotFound, Operation::Find { selector })),
 		}
 	}
-	fn find_first(&self, selector: &'static str) -> Result<Node> {
-		match self.find_all(selector).iterator.next() {
+	/// Like [`Find::find`], but returns `Ok(None)` if nothing matches, for optional parts of a page. More than one match is still
+	/// an error.
+	fn find_opt(&self, selector: impl Into<Cow<'static, str>>) -> Result<Option<Node>> {
+		let selector = selector.into();
+		self.get_document().compile_selector(&selector).map_err(|reason| self.make_error(reason, Operation::Find { selector: selector.clone() }))?;
+		let mut iter = self.find_all(selector.clone())?.iterator;
+		match (iter.next(), iter.next()) {
+			(Some(element), None) => {
+				Ok(Some(Node { document: self.get_document(), source: self.get_as_source(), operation: Operation::Find { selector }, element }))
+			},
+			(Some(_), Some(_)) => Err(self.make_error(Reason::MultipleFound, Operation::Find { selector })),
+			(None, _) => Ok(None),
+		}
+	}
+	/// Tries each selector in order and returns the first one that matches exactly one element, for markup that differs between
+	/// page templates or A/B tests. If none does, the error lists every selector that was tried. Invalid selectors fail right away.
+	fn find_any(&self, selectors: &[impl Into<Cow<'static, str>>+Clone]) -> Result<Node> {
+		let selectors: Vec<Cow<'static, str>> = selectors.iter().cloned().map(Into::into).collect();
+		for selector in &selectors {
+			self.get_document()
+				.compile_selector(selector)
+				.map_err(|reason| self.make_error(reason, Operation::FindAny { selectors: selectors.clone() }))?;
+		}
+		for selector in &selectors {
+			let mut iter = self.find_all(selector.clone())?.iterator;
+			if let (Some(element), None) = (iter.next(), iter.next()) {
+				let operation = Operation::Find { selector: selector.clone() };
+				return Ok(Node { document: self.get_document(), source: self.get_as_source(), operation, element });
+			}
+		}
+		Err(self.make_error(Reason::NotFound, Operation::FindAny { selectors }))
+	}
+	/// Like [`Find::find`], but only counts elements for which `predicate` returns true, for conditions CSS can't express, like a
+	/// row whose second cell says "Accepted". `label` describes the condition in the error trace.
+	fn find_where(
+		&self,
+		selector: impl Into<Cow<'static, str>>,
+		label: impl Into<Cow<'static, str>>,
+		predicate: impl Fn(&Node) -> bool,
+	) -> Result<Node> {
+		let selector = selector.into();
+		let label = label.into();
+		let operation = || Operation::FindWhere { selector: selector.clone(), label: label.clone() };
+		self.get_document().compile_selector(&selector).map_err(|reason| self.make_error(reason, operation()))?;
+		let mut iter = self.find_all_where(selector.clone(), label.clone(), predicate)?.iterator;
+		match (iter.next(), iter.next()) {
+			(Some(element), None) => Ok(Node { document: self.get_document(), source: self.get_as_source(), operation: operation(), element }),
+			(Some(_), Some(_)) => Err(self.make_error(Reason::MultipleFound, operation())),
+			(None, _) => Err(self.make_error(Reason::NotFound, operation())),
+		}
+	}
+	/// Like [`Find::find_all`], but only with the elements for which `predicate` returns true. See [`Find::find_where`].
+	fn find_all_where(
+		&self,
+		selector: impl Into<Cow<'static, str>>,
+		label: impl Into<Cow<'static, str>>,
+		predicate: impl Fn(&Node) -> bool,
+	) -> Result<Collection> {
+		let selector = selector.into();
+		let label = label.into();
+		let document = self.get_document();
+		document
+			.compile_selector(&selector)
+			.map_err(|reason| self.make_error(reason, Operation::FindAllWhere { selector: selector.clone(), label: label.clone(), index: 0 }))?;
+		let elements: Vec<ElementRef> = self.find_all(selector.clone())?.filter(|node| predicate(node)).map(|node| node.element).collect();
+		Ok(Collection {
+			document,
+			source: self.get_as_source(),
+			operation: Operation::FindAllWhere { selector, label, index: 0 },
+			iterator: Box::new(elements.into_iter()),
+			index: 0,
+		})
+	}
+	/// Like [`Find::find_all`] with `outer`, but only with the elements that contain a descendant matching `inner`, as CSS
+	/// `outer:has(inner)` would, which `scraper` doesn't support.
+	fn find_having(&self, outer: impl Into<Cow<'static, str>>, inner: impl Into<Cow<'static, str>>) -> Result<Collection> {
+		let outer = outer.into();
+		let inner = inner.into();
+		let document = self.get_document();
+		let operation = || Operation::FindHaving { outer: outer.clone(), inner: inner.clone(), index: 0 };
+		document.compile_selector(&outer).map_err(|reason| self.make_error(reason, operation()))?;
+		let compiled = document.compile_selector(&inner).map_err(|reason| self.make_error(reason, operation()))?;
+		let elements: Vec<ElementRef> =
+			self.find_all(outer.clone())?.map(|node| node.element).filter(|element| compiled.select(*element).next().is_some()).collect();
+		Ok(Collection { document, source: self.get_as_source(), operation: operation(), iterator: Box::new(elements.into_iter()), index: 0 })
+	}
+	fn find_first(&self, selector: impl Into<Cow<'static, str>>) -> Result<Node> {
+		let selector = selector.into();
+		self.get_document()
+			.compile_selector(&selector)
+			.map_err(|reason| self.make_error(reason, Operation::FindFirst { selector: selector.clone() }))?;
+		match self.find_all(selector.clone())?.iterator.next() {
 			Some(element) => {
 				Ok(Node { document: self.get_document(), source: self.get_as_source(), operation: Operation::FindFirst { selector }, element })
 			},
 			None => Err(self.make_error(Reason::NotFound, Operation::FindFirst { selector })),
 		}
 	}
-	fn find_nth(&self, selector: &'static str, index: usize) -> Result<Node> {
-		match self.find_all(selector).iterator.nth(index) {
+	fn find_nth(&self, selector: impl Into<Cow<'static, str>>, index: usize) -> Result<Node> {
+		let selector = selector.into();
+		self.get_document()
+			.compile_selector(&selector)
+			.map_err(|reason| self.make_error(reason, Operation::FindNth { selector: selector.clone(), index }))?;
+		match self.find_all(selector.clone())?.iterator.nth(index) {
 			Some(element) => {
 				Ok(Node { document: self.get_document(), source: self.get_as_source(), operation: Operation::FindNth { selector, index }, element })
 			},
 			None => Err(self.make_error(Reason::NotFound, Operation::FindNth { selector, index })),
 		}
 	}
+	/// Elements selected by an XPath 1.0 expression, for porting scrapers that were written with XPath. Relative paths start at this
+	/// node, or at the document for [`Document`]. Only a common subset of XPath is supported: location paths with the usual axes,
+	/// predicates with positions, comparisons, `and`, `or` and the functions `position`, `last`, `count`, `contains`, `starts-with`,
+	/// `normalize-space`, `string`, `not`, `name`, `true` and `false`, and filters like `(//li)[1]`. Text nodes and attributes can be
+	/// used in predicates, but only elements are returned.
+	fn find_all_xpath(&self, expr: impl Into<Cow<'static, str>>) -> Result<Collection> {
+		let expr = expr.into();
+		let document = self.get_document();
+		let xpath =
+			document.compile_xpath(&expr).map_err(|reason| self.make_error(reason, Operation::XPathAll { expr: expr.clone(), index: 0 }))?;
+		let context = match self.get_as_source() {
+			Some(node) => *node.element,
+			None => document.tree.tree.root(),
+		};
+		Ok(Collection {
+			document,
+			source: self.get_as_source(),
+			operation: Operation::XPathAll { expr, index: 0 },
+			iterator: Box::new(xpath.evaluate(context).into_iter()),
+			index: 0,
+		})
+	}
+	/// Like [`Find::find`], but with an XPath expression as in [`Find::find_all_xpath`].
+	fn find_xpath(&self, expr: impl Into<Cow<'static, str>>) -> Result<Node> {
+		let expr = expr.into();
+		self.get_document().compile_xpath(&expr).map_err(|reason| self.make_error(reason, Operation::XPath { expr: expr.clone() }))?;
+		let mut iter = self.find_all_xpath(expr.clone())?.iterator;
+		let element = iter.next();
+		let is_only = iter.next().is_none();
+		match element {
+			Some(element) if is_only => {
+				Ok(Node { document: self.get_document(), source: self.get_as_source(), operation: Operation::XPath { expr }, element })
+			},
+			Some(_) => Err(self.make_error(Reason::MultipleFound, Operation::XPath { expr })),
+			None => Err(self.make_error(Reason::NotFound, Operation::XPath { expr })),
+		}
+	}
+	/// Like [`Find::find`], but panics with the full error report instead of returning it. Meant for tests and examples only; scrapers
+	/// should propagate errors with `?` so callers can decide what to do with them.
+	#[cfg(feature = "test-helpers")]
+	#[track_caller]
+	fn expect_find(&self, selector: impl Into<Cow<'static, str>>) -> Node {
+		self.find(selector).unwrap_or_else(|e| panic!("{}", e.report()))
+	}
 }
 pub trait Context {
 	fn get_document(&self) -> &Document;
@@ -50,12 +243,14 @@ pub trait Context {
 	fn get_operation(&self) -> Option<Operation>;
 	fn get_as_source(&self) -> Option<&Node>;
 	fn error(&self, reason: impl fmt::Debug+fmt::Display+Send+Sync+'static) -> Error {
-		self.make_error(Reason::External(Box::new(reason)), Operation::External)
+		self.make_error(external(reason), Operation::External)
 	}
 	fn make_error(&self, reason: Reason, operation: Operation) -> Error {
 		let mut operations = self.collect_operations();
 		operations.push(operation);
-		Error { reason, operations, snapshots: self.collect_snapshots(), backtrace: Backtrace::new() }
+		let snapshots = self.collect_snapshots().into_iter().map(|snapshot| self.get_document().limit_snapshot(snapshot)).collect();
+		let backtrace = if self.get_document().capture_backtrace { Some(Backtrace::new()) } else { None };
+		Error { reason, operations, snapshots, backtrace }
 	}
 	fn collect_operations(&self) -> Vec<Operation> {
 		let mut ops = self.get_source().map_or(Vec::new(), Context::collect_operations);
@@ -65,45 +260,193 @@ pub trait Context {
 		ops
 	}
 	fn collect_snapshots(&self) -> Vec<String> {
-		let mut sss = self.get_source().map_or_else(|| vec![self.get_document().tree.root_element().html()], Context::collect_snapshots);
+		let document = self.get_document();
+		let mut sss = self.get_source().map_or_else(|| vec![document.root_snapshot()], Context::collect_snapshots);
 		if let Some(v) = self.get_as_source() {
-			sss.push(v.element.html());
+			sss.push(document.snapshot_html(v.element));
 		}
 		sss
 	}
 }
 
+/// Lifts errors from other libraries, e.g. date parsing or URL joins done on extracted values, into [`Error`]s carrying the
+/// operations and snapshots of the node, text or document they came from.
+pub trait ResultExt<T> {
+	/// Attaches `context` with [`Operation::External`] as the failing operation, like [`Text::map`].
+	fn context_node(self, context: &impl Context) -> Result<T>;
+	/// Attaches `context` with `operation` as the failing operation.
+	fn with_operation(self, context: &impl Context, operation: Operation) -> Result<T>;
+}
+impl<T, E: fmt::Debug+fmt::Display+Send+Sync+'static> ResultExt<T> for std::result::Result<T, E> {
+	fn context_node(self, context: &impl Context) -> Result<T> {
+		self.with_operation(context, Operation::External)
+	}
+
+	fn with_operation(self, context: &impl Context, operation: Operation) -> Result<T> {
+		self.map_err(|inner| context.make_error(external(inner), operation))
+	}
+}
+
+/// Types that can be extracted from a node, usually implemented with `#[derive(FromHtml)]` from the `derive` feature.
+pub trait FromHtml: Sized {
+	fn from_node(node: &Node) -> Result<Self>;
+}
+
+/// Values that are both `Debug` and `Display`, which [`Reason::External`] used to hold.
+#[deprecated(note = "`Reason::External` now holds an `Arc<dyn std::error::Error+Send+Sync>`; match on that or use `Error::downcast_ref`")]
 pub trait DebugDisplay: fmt::Debug+fmt::Display {}
-impl<T: fmt::Debug+fmt::Display> DebugDisplay for T {
+#[allow(deprecated)]
+impl<T: fmt::Debug+fmt::Display> DebugDisplay for T {}
+
+/// Wraps values that are only `Debug` and `Display`, like the `Err` of many `FromStr` impls, so they can be stored in
+/// [`Reason::External`]. [`Error::downcast_ref`] sees through it.
+struct Message<T>(T);
+impl<T: fmt::Debug> fmt::Debug for Message<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Debug::fmt(&self.0, f)
+	}
+}
+impl<T: fmt::Display> fmt::Display for Message<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&self.0, f)
+	}
+}
+impl<T: fmt::Debug+fmt::Display> std::error::Error for Message<T> {
 }
 
+/// Integer overflow with a message that names the value and the type, keeping the original error as its source.
 #[derive(Debug)]
+struct Overflow {
+	message: String,
+	source: ParseIntError,
+}
+impl fmt::Display for Overflow {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.message)
+	}
+}
+impl std::error::Error for Overflow {
+	fn source(&self) -> Option<&(dyn std::error::Error+'static)> {
+		Some(&self.source)
+	}
+}
+
+fn external(inner: impl fmt::Debug+fmt::Display+Send+Sync+'static) -> Reason {
+	Reason::External(Arc::new(Message(inner)))
+}
+
+#[derive(Clone, Debug)]
 pub enum Reason {
 	NotFound,
 	MultipleFound,
 	ExpectedElement,
 	ExpectedText,
-	External(Box<dyn DebugDisplay+Send+Sync>),
+	InvalidHtml(Vec<String>),
+	InvalidSelector(String),
+	MissingClass(String),
+	Logic(&'static str),
+	External(Arc<dyn std::error::Error+Send+Sync>),
+}
+/// [`Reason`] without its payload, for matching on the kind of failure in retry logic or error wrappers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReasonKind {
+	NotFound,
+	MultipleFound,
+	ExpectedElement,
+	ExpectedText,
+	InvalidHtml,
+	InvalidSelector,
+	MissingClass,
+	Logic,
+	External,
 }
 #[derive(Clone, Debug)]
 pub enum Operation {
-	Find { selector: &'static str },
-	FindAll { selector: &'static str, index: usize },
-	FindFirst { selector: &'static str },
-	FindNth { selector: &'static str, index: usize },
+	Find { selector: Cow<'static, str> },
+	FindAll { selector: Cow<'static, str>, index: usize },
+	FindFirst { selector: Cow<'static, str> },
+	FindNth { selector: Cow<'static, str>, index: usize },
+	FindAny { selectors: Vec<Cow<'static, str>> },
+	FindWhere { selector: Cow<'static, str>, label: Cow<'static, str> },
+	FindAllWhere { selector: Cow<'static, str>, label: Cow<'static, str>, index: usize },
+	FindHaving { outer: Cow<'static, str>, inner: Cow<'static, str>, index: usize },
+	XPath { expr: Cow<'static, str> },
+	XPathAll { expr: Cow<'static, str>, index: usize },
 	Child { index: usize },
+	ChildElement { index: usize },
 	ChildText { index: usize },
+	PrevText,
+	NextText,
 	Parent,
+	Ancestor { depth: usize },
+	Closest { selector: Cow<'static, str> },
+	ClosestWithin { selector: Cow<'static, str>, max_depth: usize },
+	ClosestForm,
+	Form,
+	Microdata,
+	FormField { name: String },
+	ClosestLink,
+	PrevHeading,
 	Text,
 	TextMultiline,
-	Attr { key: &'static str },
-	Parse,
+	TextCollapsed,
+	TextBlocks,
+	TextUntil { selector: Cow<'static, str> },
+	SiblingBefore { index: usize },
+	SiblingAfter { index: usize },
+	Sibling { index: usize },
+	NextSibling,
+	PrevSibling,
+	NthOfType { tag: String, index: usize },
+	Attr { key: Cow<'static, str> },
+	AttrParse { key: Cow<'static, str>, ty: &'static str },
+	RequireClass { class: String },
+	AbsHref,
+	Href,
+	ImageSrc { index: usize },
+	LinkHref { index: usize },
+	Url,
+	CellHeader,
+	Table,
+	TableColumn { header: String },
+	TableCell { row: usize, column: usize },
+	ParseHtml,
+	Fetch { url: String },
+	ParseSelector { selector: Cow<'static, str> },
+	CanonicalUrl,
+	Language,
+	Meta { name: String },
+	OpenGraph { property: String },
+	JsonLd { index: usize },
+	Json,
+	ScriptById { id: String },
+	Parse { ty: &'static str },
+	TextParse { ty: &'static str },
+	ParseDatetime { formats: Vec<String> },
+	Datetime,
+	Regex { pattern: String },
+	EnsureNonempty,
+	MapKey,
+	Owned { operation: Box<Operation>, marker: usize },
 	External,
 }
 
 pub struct Document {
 	pub tree: scraper::Html,
-	selector_cache: arena_cache::ArenaCache<&'static str, Selector>,
+	selector_cache: arena_cache::ArenaCache<String, std::result::Result<CompiledSelector, String>>,
+	xpath_cache: arena_cache::ArenaCache<String, std::result::Result<xpath::XPath, String>>,
+	#[cfg(feature = "regex")]
+	regex_cache: arena_cache::ArenaCache<String, std::result::Result<regex::Regex, String>>,
+	html_len_cache: arena_cache::ArenaCache<ego_tree::NodeId, usize>,
+	trim_policy: TrimPolicy,
+	snapshot_policy: SnapshotPolicy,
+	redacted_attrs: Vec<String>,
+	capture_backtrace: bool,
+	#[cfg(feature = "url")]
+	base: Option<url::Url>,
+	#[cfg(feature = "url")]
+	document_url: Option<url::Url>,
+	url: Option<String>,
 }
 pub struct Node<'a> {
 	document: &'a Document,
@@ -114,28 +457,347 @@ pub struct Node<'a> {
 pub struct Collection<'a> {
 	document: &'a Document,
 	source: Option<&'a Node<'a>>,
-	selector: &'static str,
-	iterator: scraper::element_ref::Select<'a, 'a>,
+	operation: Operation,
+	iterator: Box<dyn Elements<'a>+'a>,
 	index: usize,
 }
+trait Elements<'a>: Iterator<Item = ElementRef<'a>> {
+	fn clone_box(&self) -> Box<dyn Elements<'a>+'a>;
+}
+impl<'a, I: Iterator<Item = ElementRef<'a>>+Clone+'a> Elements<'a> for I {
+	fn clone_box(&self) -> Box<dyn Elements<'a>+'a> {
+		Box::new(self.clone())
+	}
+}
 pub struct Text<'a> {
 	document: &'a Document,
-	source: &'a Node<'a>,
+	source: Option<&'a Node<'a>>,
+	/// Nodes between `source` and this text that can't be borrowed, like the row and field of [`Collection::with_field`].
+	detached: Vec<(Operation, ElementRef<'a>)>,
 	operation: Operation,
 	value: String,
 }
 
 impl Document {
 	pub fn new(html: &str) -> Document {
-		Document { tree: scraper::Html::parse_document(html), selector_cache: arena_cache::ArenaCache::new() }
+		Document::from_html(scraper::Html::parse_document(html))
+	}
+
+	/// Parses like [`Document::new`], but fails with [`Reason::InvalidHtml`] if the parser had to recover from any errors, e.g.
+	/// because the page was truncated. Same as [`DocumentBuilder::strict`].
+	pub fn new_strict(html: &str) -> Result<Document> {
+		DocumentBuilder::new().strict(true).parse(html)
+	}
+
+	/// Parses a snippet of HTML, like an AJAX response, without the `<head>` and `<body>` that [`Document::new`] adds around it.
+	/// The top-level elements of the snippet are children of a single `<html>` root element, so they can be matched with
+	/// `:root > div`.
+	pub fn new_fragment(html: &str) -> Document {
+		Document::from_html(scraper::Html::parse_fragment(html))
+	}
+
+	/// Decodes and parses a page given as raw bytes, e.g. a response body. The encoding is taken from a byte order mark, the
+	/// `charset` in `content_type` (the value of the `Content-Type` header, if known) or a `<meta charset>` in the page, in that
+	/// order, and defaults to UTF-8, or windows-1252 for bytes that aren't valid UTF-8. Invalid sequences are replaced with U+FFFD.
+	#[cfg(feature = "encoding")]
+	pub fn from_bytes(bytes: &[u8], content_type: Option<&str>) -> Document {
+		let (html, _, _) = charset::detect(bytes, content_type).decode(bytes);
+		Document::new(&html)
+	}
+
+	/// Wraps a tree that was already parsed, e.g. with custom `scraper` options, without parsing it again.
+	pub fn from_html(tree: scraper::Html) -> Document {
+		Document {
+			tree,
+			selector_cache: arena_cache::ArenaCache::new(),
+			xpath_cache: arena_cache::ArenaCache::new(),
+			#[cfg(feature = "regex")]
+			regex_cache: arena_cache::ArenaCache::new(),
+			html_len_cache: arena_cache::ArenaCache::new(),
+			trim_policy: TrimPolicy::Trim,
+			snapshot_policy: SnapshotPolicy::new(),
+			redacted_attrs: Vec::new(),
+			capture_backtrace: backtrace_by_default(),
+			#[cfg(feature = "url")]
+			base: None,
+			#[cfg(feature = "url")]
+			document_url: None,
+			url: None,
+		}
+	}
+
+	/// Downloads and parses the page at `url`. The final URL after redirects is used for resolving links when the page has no
+	/// `<base href>` tag, and is added as a comment at the start of the page snapshot in errors, so they tell which page was being
+	/// parsed. Responses with an error status fail with [`Operation::Fetch`] and no snapshots.
+	#[cfg(feature = "fetch")]
+	pub async fn fetch(url: &str) -> Result<Document> {
+		let fail = |inner: reqwest::Error| fetch_error(url, inner);
+		let response = reqwest::get(url).await.and_then(reqwest::Response::error_for_status).map_err(fail)?;
+		let base = response.url().clone();
+		let html = response.text().await.map_err(fail)?;
+		Ok(Document::fetched(&html, base))
+	}
+
+	/// Blocking version of [`Document::fetch`], which must not be called from an async runtime.
+	#[cfg(feature = "fetch")]
+	pub fn fetch_blocking(url: &str) -> Result<Document> {
+		let fail = |inner: reqwest::Error| fetch_error(url, inner);
+		let response = reqwest::blocking::get(url).and_then(reqwest::blocking::Response::error_for_status).map_err(fail)?;
+		let base = response.url().clone();
+		let html = response.text().map_err(fail)?;
+		Ok(Document::fetched(&html, base))
+	}
+
+	#[cfg(feature = "fetch")]
+	fn fetched(html: &str, url: url::Url) -> Document {
+		let mut document = Document::new(html);
+		document.url = Some(url.to_string());
+		document.document_url = Some(url);
+		document
+	}
+
+	/// Whether errors created from now on capture a backtrace. Capturing is slow enough to dominate code that probes for optional
+	/// elements and expects most lookups to fail. It's on by default, unless the `RUST_BACKTRACE` environment variable is `0`.
+	pub fn set_capture_backtrace(&mut self, capture_backtrace: bool) {
+		self.capture_backtrace = capture_backtrace;
+	}
+
+	/// Changes how snapshots are captured in errors created from now on.
+	pub fn set_snapshot_policy(&mut self, snapshot_policy: SnapshotPolicy) {
+		self.snapshot_policy = snapshot_policy;
+	}
+
+	/// Sets the base used by [`Node::href`] and [`Node::abs_href`] to resolve relative links. It takes precedence over the page's
+	/// own `<base href>` tag and over the URL a fetched page was downloaded from.
+	#[cfg(feature = "url")]
+	pub fn set_base(&mut self, base: &str) -> std::result::Result<(), url::ParseError> {
+		self.base = Some(url::Url::parse(base)?);
+		Ok(())
+	}
+
+	/// Like [`Document::set_base`], for a URL that's already parsed.
+	#[cfg(feature = "url")]
+	pub fn set_base_url(&mut self, base: url::Url) {
+		self.base = Some(base);
 	}
 
 	pub fn html(&self) -> String {
 		self.tree.root_element().html()
 	}
 
-	fn compile_selector(&self, selector: &'static str) -> &Selector {
-		self.selector_cache.query(selector, |selector| scraper::Selector::parse(selector).unwrap())
+	/// Text of the whole page as a reader would see it, for indexing. Skips `<head>`, scripts, styles and templates, separates block
+	/// elements with whitespace and collapses whitespace runs to a single space.
+	pub fn all_text(&self) -> String {
+		let mut text = String::new();
+		write_visible_text(self.tree.root_element(), &mut text);
+		collapse_whitespace(&text).trim().to_owned()
+	}
+
+	/// Target of the first `<link rel="canonical">`, matching `rel` as a case-insensitive token list.
+	pub fn canonical_url(&self) -> Option<Text> {
+		let link = self
+			.tree
+			.select(self.builtin_selector("link[rel][href]"))
+			.find(|link| link.value().attr("rel").unwrap().split_whitespace().any(|rel| rel.eq_ignore_ascii_case("canonical")))?;
+		Some(Text {
+			document: self,
+			source: None,
+			detached: Vec::new(),
+			operation: Operation::CanonicalUrl,
+			value: link.value().attr("href").unwrap().trim().to_owned(),
+		})
+	}
+
+	/// Every `<img>` with its source. The source is `src`, unless it's missing or a `data:` placeholder, in which case the lazy
+	/// loading attributes `data-src`, `data-lazy-src` and `data-original` are tried in that order. Images without any source are
+	/// skipped.
+	pub fn images(&self) -> impl Iterator<Item = (Node, Text)> {
+		self.find_all("img").unwrap().enumerate().filter_map(move |(index, image)| {
+			let element = image.element.value();
+			let src = element.attr("src").map(str::trim).filter(|src| !src.is_empty());
+			let lazy = ["data-src", "data-lazy-src", "data-original"]
+				.iter()
+				.filter_map(|key| element.attr(key))
+				.map(str::trim)
+				.find(|src| !src.is_empty());
+			let src = match src {
+				Some(src) if !src.starts_with("data:") => src,
+				_ => lazy.or(src)?,
+			};
+			let src = Text { document: self, source: None, detached: Vec::new(), operation: Operation::ImageSrc { index }, value: src.to_owned() };
+			Some((image, src))
+		})
+	}
+
+	/// Every `<a href>` on the page with its `href`, which can be resolved with [`Text::url`]. The link text is available from the
+	/// node.
+	pub fn links(&self) -> impl Iterator<Item = (Node, Text)> {
+		links(self.find_all("a[href]").unwrap())
+	}
+
+	/// Language of the page from `<html lang>`, or else from the first `<meta http-equiv="content-language">`.
+	pub fn language(&self) -> Option<Text> {
+		let value = self.tree.root_element().value().attr("lang").filter(|lang| !lang.trim().is_empty()).or_else(|| {
+			self.tree
+				.select(self.builtin_selector("meta[http-equiv][content]"))
+				.find(|meta| meta.value().attr("http-equiv").unwrap().eq_ignore_ascii_case("content-language"))
+				.map(|meta| meta.value().attr("content").unwrap())
+		})?;
+		Some(Text { document: self, source: None, detached: Vec::new(), operation: Operation::Language, value: value.trim().to_owned() })
+	}
+
+	/// Content of the first `<meta name="...">` with the given name, compared case-insensitively, like `description` or `author`.
+	pub fn meta(&self, name: &str) -> Result<Text> {
+		let operation = Operation::Meta { name: name.to_owned() };
+		let meta = self
+			.tree
+			.select(self.builtin_selector("meta[name][content]"))
+			.find(|meta| meta.value().attr("name").unwrap().trim().eq_ignore_ascii_case(name))
+			.ok_or_else(|| self.make_error(Reason::NotFound, operation.clone()))?;
+		Ok(Text { document: self, source: None, detached: Vec::new(), operation, value: meta.value().attr("content").unwrap().trim().to_owned() })
+	}
+
+	/// Content of the first Open Graph tag for `property`, given without the `og:` prefix, e.g. `og("image")`. Tags using `name`
+	/// instead of `property` are accepted too, since many sites get this wrong.
+	pub fn og(&self, property: &str) -> Result<Text> {
+		let operation = Operation::OpenGraph { property: property.to_owned() };
+		let key = format!("og:{}", property);
+		let meta = self
+			.tree
+			.select(self.builtin_selector("meta[content]"))
+			.find(|meta| {
+				let element = meta.value();
+				element.attr("property").or_else(|| element.attr("name")).is_some_and(|candidate| candidate.trim().eq_ignore_ascii_case(&key))
+			})
+			.ok_or_else(|| self.make_error(Reason::NotFound, operation.clone()))?;
+		Ok(Text { document: self, source: None, detached: Vec::new(), operation, value: meta.value().attr("content").unwrap().trim().to_owned() })
+	}
+
+	/// Title, description, canonical URL and Open Graph and Twitter Card tags, collected in one pass over the page.
+	pub fn metadata(&self) -> Metadata {
+		metadata::collect(self)
+	}
+
+	/// Errors the parser recovered from, in the order they were found. Most real pages have some, so this is mostly useful for
+	/// comparing against a known-good version of the page.
+	pub fn parse_errors(&self) -> impl Iterator<Item = &str> {
+		self.tree.errors.iter().map(|error| error.as_ref())
+	}
+
+	/// Top-level microdata items on the page, i.e. `itemscope` elements that aren't a property of another item.
+	pub fn microdata(&self) -> Vec<Item> {
+		microdata::top_level(self)
+	}
+
+	/// Contents of every `<script type="application/ld+json">`, ready to be deserialized with e.g. `serde_json`.
+	pub fn json_ld(&self) -> Vec<Text> {
+		self.tree
+			.select(self.builtin_selector("script[type]"))
+			.filter(|script| {
+				let kind = script.value().attr("type").unwrap();
+				kind.split(';').next().unwrap().trim().eq_ignore_ascii_case("application/ld+json")
+			})
+			.enumerate()
+			.map(|(index, script)| Text {
+				document: self,
+				source: None,
+				detached: Vec::new(),
+				operation: Operation::JsonLd { index },
+				value: script.text().collect::<String>().trim().to_owned(),
+			})
+			.collect()
+	}
+
+	/// Contents of the `<script>` with the given `id`, for data blobs like Next.js' `__NEXT_DATA__`.
+	pub fn script_by_id(&self, id: &str) -> Option<Text> {
+		let script = self.tree.select(self.builtin_selector("script[id]")).find(|script| script.value().id() == Some(id))?;
+		let value = script.text().collect::<String>().trim().to_owned();
+		Some(Text { document: self, source: None, detached: Vec::new(), operation: Operation::ScriptById { id: id.to_owned() }, value })
+	}
+
+	/// Finds the single JSON `<script>` matching `selector` and deserializes it. See [`Node::json`].
+	#[cfg(feature = "json")]
+	pub fn find_json<T: serde::de::DeserializeOwned>(&self, selector: impl Into<Cow<'static, str>>) -> Result<T> {
+		self.find(selector)?.json()
+	}
+
+	/// Checks a selector up front, e.g. one read from a config file, instead of on first use. The compiled selector is cached, so
+	/// this costs nothing later.
+	pub fn validate_selector(&self, selector: impl Into<Cow<'static, str>>) -> Result<()> {
+		let selector = selector.into();
+		match self.compile_selector(&selector) {
+			Ok(_) => Ok(()),
+			Err(reason) => Err(self.make_error(reason, Operation::ParseSelector { selector })),
+		}
+	}
+
+	fn compile_selector(&self, selector: &str) -> std::result::Result<&CompiledSelector, Reason> {
+		let compiled = self.selector_cache.query(selector, CompiledSelector::parse);
+		compiled.as_ref().map_err(|error| Reason::InvalidSelector(error.clone()))
+	}
+
+	fn compile_xpath(&self, expr: &str) -> std::result::Result<&xpath::XPath, Reason> {
+		let compiled = self.xpath_cache.query(expr, xpath::XPath::parse);
+		compiled.as_ref().map_err(|error| Reason::InvalidSelector(error.clone()))
+	}
+
+	#[cfg(feature = "regex")]
+	fn compile_regex(&self, pattern: &str) -> std::result::Result<&regex::Regex, Reason> {
+		let compiled = self.regex_cache.query(pattern, |pattern| regex::Regex::new(pattern).map_err(|error| error.to_string()));
+		compiled.as_ref().map_err(|error| external(error.clone()))
+	}
+
+	/// Compiles one of the crate's own selectors, which are known to be valid.
+	fn builtin_selector(&self, selector: &'static str) -> &Selector {
+		self.compile_selector(selector).unwrap().as_css().unwrap()
+	}
+
+	fn trim_text(&self, text: &str) -> String {
+		match self.trim_policy {
+			TrimPolicy::Trim => text.trim().to_owned(),
+			TrimPolicy::Collapse => collapse_whitespace(text).trim().to_owned(),
+			TrimPolicy::Keep => text.to_owned(),
+		}
+	}
+
+	fn trim_multiline_text(&self, text: &str) -> String {
+		match self.trim_policy {
+			TrimPolicy::Collapse => {
+				text.lines().map(|line| collapse_whitespace(line).trim().to_owned()).collect::<Vec<_>>().join("\n").trim().to_owned()
+			},
+			_ => self.trim_text(text),
+		}
+	}
+
+	/// Serialized once when the error is created and stored in it, so formatting the error again (logging, displaying, serializing
+	/// it) never walks the tree. Nothing is kept in the document, which would hold a copy of every subtree that ever failed.
+	fn snapshot_html(&self, element: ElementRef) -> String {
+		let redact: Vec<&str> = self.redacted_attrs.iter().map(String::as_str).collect();
+		serialize(element, &redact)
+	}
+
+	fn root_snapshot(&self) -> String {
+		let html = self.snapshot_html(self.tree.root_element());
+		match &self.url {
+			Some(url) => format!("<!-- {} -->\n{}", url, html),
+			None => html,
+		}
+	}
+
+	fn limit_snapshot(&self, snapshot: String) -> String {
+		self.snapshot_policy.apply(snapshot)
+	}
+
+	#[cfg(feature = "url")]
+	fn base_url(&self) -> Option<url::Url> {
+		let tag = || {
+			let href = self.tree.select(self.builtin_selector("base[href]")).next()?.value().attr("href")?;
+			match &self.document_url {
+				Some(document_url) => document_url.join(href).ok(),
+				None => url::Url::parse(href).ok(),
+			}
+		};
+		self.base.clone().or_else(tag).or_else(|| self.document_url.clone())
 	}
 }
 impl Context for Document {
@@ -156,8 +818,18 @@ impl Context for Document {
 	}
 }
 impl Find for Document {
-	fn find_all(&self, selector: &'static str) -> Collection {
-		Collection { document: self, source: None, selector, iterator: self.tree.root_element().select(self.compile_selector(selector)), index: 0 }
+	fn find_all(&self, selector: impl Into<Cow<'static, str>>) -> Result<Collection> {
+		let selector = selector.into();
+		let compiled = self
+			.compile_selector(&selector)
+			.map_err(|reason| self.make_error(reason, Operation::FindAll { selector: selector.clone(), index: 0 }))?;
+		Ok(Collection {
+			document: self,
+			source: None,
+			iterator: Box::new(compiled.select(self.tree.root_element())),
+			operation: Operation::FindAll { selector, index: 0 },
+			index: 0,
+		})
 	}
 }
 
@@ -174,15 +846,112 @@ impl<'a> Node<'a> {
 		}
 	}
 
+	/// Element children of this node, skipping text and comments, so indices don't shift when whitespace between tags changes.
+	pub fn children_elements(&self) -> Collection {
+		Collection {
+			document: self.document,
+			source: Some(self),
+			operation: Operation::ChildElement { index: 0 },
+			iterator: Box::new(self.element.children().filter_map(ElementRef::wrap)),
+			index: 0,
+		}
+	}
+
+	/// Number of element children of this node.
+	pub fn child_count(&self) -> usize {
+		self.element.children().filter_map(ElementRef::wrap).count()
+	}
+
+	/// Like [`Find::find_all`], but only matches descendants at most `max_depth` levels below this node, where children are at
+	/// depth 1. This keeps an outer list's items from picking up the items of lists nested inside them.
+	pub fn find_all_within_depth(&self, selector: impl Into<Cow<'static, str>>, max_depth: usize) -> Result<Collection> {
+		let selector = selector.into();
+		let compiled = self
+			.document
+			.compile_selector(&selector)
+			.map_err(|reason| self.make_error(reason, Operation::FindAll { selector: selector.clone(), index: 0 }))?;
+		let root = self.element;
+		let within_depth =
+			move |element: &ElementRef| element.ancestors().position(|ancestor| ancestor.id() == root.id()).is_some_and(|depth| depth < max_depth);
+		Ok(Collection {
+			document: self.document,
+			source: Some(self),
+			iterator: Box::new(compiled.select(self.element).filter(within_depth)),
+			operation: Operation::FindAll { selector, index: 0 },
+			index: 0,
+		})
+	}
+
+	/// Like [`Find::find_all`], but this node itself is also a candidate. If it matches, it comes first, followed by the matching
+	/// descendants in document order.
+	pub fn find_all_self(&self, selector: impl Into<Cow<'static, str>>) -> Result<Collection> {
+		let selector = selector.into();
+		let compiled = self
+			.document
+			.compile_selector(&selector)
+			.map_err(|reason| self.make_error(reason, Operation::FindAll { selector: selector.clone(), index: 0 }))?;
+		let this = std::iter::once(self.element).filter(move |element| compiled.matches(element));
+		Ok(Collection {
+			document: self.document,
+			source: Some(self),
+			iterator: Box::new(this.chain(compiled.select(self.element))),
+			operation: Operation::FindAll { selector, index: 0 },
+			index: 0,
+		})
+	}
+
+	/// Number of descendants matching `selector`, without building nodes for them.
+	pub fn count(&self, selector: impl Into<Cow<'static, str>>) -> Result<usize> {
+		Ok(self.find_all(selector)?.iterator.count())
+	}
+
 	pub fn text_child(&self, index: usize) -> Result<Text> {
 		match self.element.children().nth(index) {
 			Some(node) => Ok(Text {
 				document: self.document,
-				source: self,
+				source: Some(self),
+				detached: Vec::new(),
 				operation: Operation::ChildText { index },
-				value: node.value().as_text().ok_or_else(|| self.make_error(Reason::ExpectedText, Operation::ChildText { index }))?.trim().to_owned(),
+				value: self
+					.document
+					.trim_text(node.value().as_text().ok_or_else(|| self.make_error(Reason::ExpectedText, Operation::ChildText { index }))?),
 			}),
-			None => Err(self.make_error(Reason::NotFound, Operation::Child { index })),
+			None => Err(self.make_error(Reason::NotFound, Operation::ChildText { index })),
+		}
+	}
+
+	/// Like [`Node::text_child`], but returns `Ok(None)` if the child is not a text node, for iterating over mixed content.
+	pub fn try_text_child(&self, index: usize) -> Result<Option<Text>> {
+		match self.element.children().nth(index) {
+			Some(node) => Ok(node.value().as_text().map(|text| Text {
+				document: self.document,
+				source: Some(self),
+				detached: Vec::new(),
+				operation: Operation::ChildText { index },
+				value: self.document.trim_text(text),
+			})),
+			None => Err(self.make_error(Reason::NotFound, Operation::ChildText { index })),
+		}
+	}
+
+	pub fn prev_text(&self) -> Result<Text> {
+		self.adjacent_text(self.element.prev_sibling().map(|node| node.value()), Operation::PrevText)
+	}
+
+	pub fn next_text(&self) -> Result<Text> {
+		self.adjacent_text(self.element.next_sibling().map(|node| node.value()), Operation::NextText)
+	}
+
+	fn adjacent_text(&self, sibling: Option<&scraper::Node>, operation: Operation) -> Result<Text> {
+		match sibling {
+			Some(node) => Ok(Text {
+				document: self.document,
+				source: Some(self),
+				detached: Vec::new(),
+				value: self.document.trim_text(node.as_text().ok_or_else(|| self.make_error(Reason::ExpectedText, operation.clone()))?),
+				operation,
+			}),
+			None => Err(self.make_error(Reason::NotFound, operation)),
 		}
 	}
 
@@ -198,105 +967,1038 @@ impl<'a> Node<'a> {
 		}
 	}
 
-	pub fn text(&self) -> Text {
-		let mut value = String::new();
-		for chunk in self.element.text() {
-			value += chunk;
-		}
-		Text { document: self.document, source: self, operation: Operation::Text, value: value.trim().to_owned() }
+	/// The `index`-th element named `tag` among this node's siblings (including itself), like CSS `:nth-of-type` but zero-based.
+	pub fn nth_of_type(&self, tag: &str, index: usize) -> Result<Node> {
+		let operation = || Operation::NthOfType { tag: tag.to_owned(), index };
+		let element = self
+			.element
+			.parent()
+			.and_then(|parent| parent.children().filter_map(ElementRef::wrap).filter(|element| element.value().name() == tag).nth(index))
+			.ok_or_else(|| self.make_error(Reason::NotFound, operation()))?;
+		Ok(Node { document: self.document, source: Some(self), operation: operation(), element })
 	}
 
-	pub fn text_multiline(&self) -> Text {
-		let mut value = String::new();
-		for v in self.element.descendants() {
-			match v.value() {
-				scraper::node::Node::Text(text) => value += &*text,
-				scraper::node::Node::Element(element) if element.name() == "br" => value += "\n",
-				_ => (),
-			}
-		}
-		Text { document: self.document, source: self, operation: Operation::TextMultiline, value: value.trim().to_owned() }
+	/// Position of this node among its siblings with the same tag name, starting from zero.
+	pub fn type_index(&self) -> usize {
+		let name = self.element.value().name();
+		self.element.prev_siblings().filter_map(ElementRef::wrap).filter(|element| element.value().name() == name).count()
 	}
 
-	pub fn attr(&self, key: &'static str) -> Result<Text> {
-		let value = self.element.value().attr(key).ok_or_else(|| self.make_error(Reason::NotFound, Operation::Attr { key }))?;
-		Ok(Text { document: self.document, source: self, operation: Operation::Attr { key }, value: value.to_owned() })
+	/// Whether this node is strictly inside `other`. Compares tree positions rather than HTML, so identical markup elsewhere on the
+	/// page doesn't count, and nodes from different documents are never related.
+	pub fn is_descendant_of(&self, other: &Node) -> bool {
+		std::ptr::eq(self.document, other.document) && self.element.ancestors().any(|ancestor| ancestor.id() == other.element.id())
 	}
-}
-impl<'a> Context for Node<'a> {
-	fn get_document(&self) -> &Document {
-		self.document
+
+	/// Whether `other` is strictly inside this node.
+	pub fn is_ancestor_of(&self, other: &Node) -> bool {
+		other.is_descendant_of(self)
 	}
 
-	fn get_source(&self) -> Option<&Node> {
-		self.source
+	/// Whether any descendant of this node matches `selector`, like CSS `:has()`. Fails only if the selector is invalid.
+	pub fn has(&self, selector: impl Into<Cow<'static, str>>) -> Result<bool> {
+		Ok(self.find_all(selector)?.next().is_some())
 	}
 
-	fn get_operation(&self) -> Option<Operation> {
-		Some(self.operation.clone())
+	/// Element ancestors of this node, from the parent up to `<html>`.
+	pub fn ancestors(&self) -> impl Iterator<Item = Node> {
+		self.element
+			.ancestors()
+			.filter_map(ElementRef::wrap)
+			.enumerate()
+			.map(move |(i, element)| Node { document: self.document, source: Some(self), operation: Operation::Ancestor { depth: i + 1 }, element })
 	}
 
-	fn get_as_source(&self) -> Option<&Node> {
-		Some(self)
+	/// Nearest ancestor for which `predicate` returns true. Returns an `Option` so that wrappers can report a more specific error.
+	pub fn ancestors_find(&self, predicate: impl Fn(&Node) -> bool) -> Option<Node> {
+		self.ancestors().find(|node| predicate(node))
 	}
-}
-impl<'a> Find for Node<'a> {
-	fn find_all(&self, selector: &'static str) -> Collection {
-		Collection {
-			document: self.document,
-			source: Some(self),
-			selector,
-			iterator: self.element.select(self.document.compile_selector(selector)),
-			index: 0,
+
+	/// Nearest element matching `selector`, starting with this node itself, like the DOM's `closest()`.
+	pub fn closest(&self, selector: impl Into<Cow<'static, str>>) -> Result<Node> {
+		let selector = selector.into();
+		let compiled =
+			self.document.compile_selector(&selector).map_err(|reason| self.make_error(reason, Operation::Closest { selector: selector.clone() }))?;
+		match std::iter::once(self.element).chain(self.element.ancestors().filter_map(ElementRef::wrap)).find(|element| compiled.matches(element)) {
+			Some(element) => Ok(Node { document: self.document, source: Some(self), operation: Operation::Closest { selector }, element }),
+			None => Err(self.make_error(Reason::NotFound, Operation::Closest { selector })),
 		}
 	}
-}
 
-impl<'a> Iterator for Collection<'a> {
-	type Item = Node<'a>;
+	/// Nearest element matching `selector`, starting with this node itself and climbing at most `max_depth` levels up, so a
+	/// page-wide container further away isn't picked up by accident.
+	pub fn closest_within(&self, selector: impl Into<Cow<'static, str>>, max_depth: usize) -> Result<Node> {
+		let selector = selector.into();
+		let compiled = self
+			.document
+			.compile_selector(&selector)
+			.map_err(|reason| self.make_error(reason, Operation::ClosestWithin { selector: selector.clone(), max_depth }))?;
+		let element = std::iter::once(self.element)
+			.chain(self.element.ancestors().filter_map(ElementRef::wrap))
+			.take(max_depth + 1)
+			.find(|element| compiled.matches(element));
+		match element {
+			Some(element) => {
+				Ok(Node { document: self.document, source: Some(self), operation: Operation::ClosestWithin { selector, max_depth }, element })
+			},
+			None => Err(self.make_error(Reason::NotFound, Operation::ClosestWithin { selector, max_depth })),
+		}
+	}
 
-	fn next(&mut self) -> Option<Node<'a>> {
-		self.iterator.next().map(|element| {
-			let node = Node {
-				document: self.document,
-				operation: Operation::FindAll { selector: self.selector, index: self.index },
-				source: self.source,
-				element,
-			};
-			self.index += 1;
-			node
-		})
+	/// Nearest `<h1>` to `<h6>` before this node, i.e. the title of the section it's in. Preceding siblings are searched first,
+	/// nearest first, with a sibling counting if it's a heading or contains one (the last one inside it wins). If none is found, the
+	/// search moves up a level and continues with the parent's preceding siblings.
+	pub fn prev_heading(&self) -> Result<Node> {
+		let is_heading = |element: &ElementRef| ["h1", "h2", "h3", "h4", "h5", "h6"].contains(&element.value().name());
+		let heading = std::iter::once(self.element).chain(self.element.ancestors().filter_map(ElementRef::wrap)).find_map(|level| {
+			level.prev_siblings().filter_map(ElementRef::wrap).find_map(|sibling| {
+				if is_heading(&sibling) { Some(sibling) } else { sibling.descendants().filter_map(ElementRef::wrap).filter(is_heading).last() }
+			})
+		});
+		match heading {
+			Some(element) => Ok(Node { document: self.document, source: Some(self), operation: Operation::PrevHeading, element }),
+			None => Err(self.make_error(Reason::NotFound, Operation::PrevHeading)),
+		}
 	}
-}
 
-impl<'a> Text<'a> {
-	pub fn string(&self) -> String {
-		self.value.clone()
+	pub fn closest_form(&self) -> Result<Node> {
+		self.ancestors_find(|node| node.element.value().name() == "form").ok_or_else(|| self.make_error(Reason::NotFound, Operation::ClosestForm))
 	}
 
-	pub fn as_str(&self) -> &str {
-		&self.value
+	/// `href` of the nearest enclosing `<a>`, starting with this node itself, i.e. where clicking on this element leads. Anchors
+	/// without an `href` are skipped, since they aren't links.
+	pub fn closest_link(&self) -> Result<Text> {
+		let mut candidates = std::iter::once(self.element).chain(self.element.ancestors().filter_map(ElementRef::wrap));
+		let href = candidates.find_map(|element| match element.value().name() {
+			"a" => element.value().attr("href"),
+			_ => None,
+		});
+		let href = href.ok_or_else(|| self.make_error(Reason::NotFound, Operation::ClosestLink))?;
+		Ok(Text { document: self.document, source: Some(self), detached: Vec::new(), operation: Operation::ClosestLink, value: href.to_owned() })
 	}
 
-	pub fn parse<T>(&self) -> Result<T>
+	pub fn text(&self) -> Text {
+		let mut value = String::new();
+		for chunk in self.element.text() {
+			value += chunk;
+		}
+		Text { document: self.document, source: Some(self), detached: Vec::new(), operation: Operation::Text, value: self.document.trim_text(&value) }
+	}
+
+	/// Shorthand for `text().parse()`, recorded as one operation that names the target type.
+	pub fn text_parse<T>(&self) -> Result<T>
 	where
 		T: FromStr+'static,
 		<T as FromStr>::Err: fmt::Debug+fmt::Display+Send+Sync+'static,
 	{
-		self.value.parse().map_err(|inner| self.make_error(Reason::External(Box::new(inner)), Operation::Parse))
+		let text = self.text();
+		text.value.parse().map_err(|inner| self.make_error(parse_reason::<T>(&text.value, inner), Operation::TextParse { ty: type_name::<T>() }))
 	}
 
-	pub fn map<T, E: fmt::Debug+fmt::Display+Send+Sync+'static>(&self, f: impl FnOnce(&str) -> std::result::Result<T, E>) -> Result<T> {
-		f(&self.value).map_err(|inner| self.make_error(Reason::External(Box::new(inner)), Operation::External))
+	/// Date and time of a `<time>` element, read from its `datetime` attribute, or from its text if there is none, as HTML does.
+	/// Times with an offset are converted to UTC, and dates without a time give midnight.
+	#[cfg(feature = "chrono")]
+	pub fn datetime(&self) -> Result<chrono::NaiveDateTime> {
+		let text = match self.element.value().attr("datetime") {
+			Some(value) => value.to_owned(),
+			None => self.element.text().collect(),
+		};
+		let value = text.trim();
+		// RFC 3339 requires seconds, which HTML doesn't, so `Z` is spelled out for the formats below to handle it.
+		let zoned = match value.strip_suffix('Z').or_else(|| value.strip_suffix('z')) {
+			Some(local) => format!("{}+00:00", local),
+			None => value.to_owned(),
+		};
+		let datetime = chrono::DateTime::parse_from_rfc3339(value)
+			.or_else(|_| chrono::DateTime::parse_from_str(&zoned, "%Y-%m-%dT%H:%M:%S%.f%z"))
+			.or_else(|_| chrono::DateTime::parse_from_str(&zoned, "%Y-%m-%d %H:%M:%S%.f%z"))
+			.or_else(|_| chrono::DateTime::parse_from_str(&zoned, "%Y-%m-%dT%H:%M%z"))
+			.or_else(|_| chrono::DateTime::parse_from_str(&zoned, "%Y-%m-%d %H:%M%z"))
+			.map(|datetime| datetime.naive_utc());
+		let formats = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M", "%Y-%m-%d"];
+		datetime.ok().or_else(|| formats.iter().find_map(|format| parse_naive_datetime(value, format).ok())).ok_or_else(|| {
+			self.make_error(external(format!("{:?} isn't a valid date or time", value)), Operation::Datetime)
+		})
 	}
-}
-impl<'a> Context for Text<'a> {
-	fn get_document(&self) -> &Document {
-		self.document
+
+	/// Same as jQuery's `.text()`, for porting scrapers written with it. All descendant text nodes are concatenated in document order
+	/// with entities decoded, including the contents of `<script>` and `<style>`. Whitespace is kept exactly as it is, regardless of
+	/// the document's [`TrimPolicy`], and `<br>` adds nothing.
+	pub fn jquery_text(&self) -> String {
+		self.element.text().collect()
+	}
+
+	/// Whether [`Node::text`] equals `expected`, for use in filters.
+	pub fn text_eq(&self, expected: &str) -> bool {
+		self.element.text().collect::<String>().trim() == expected
+	}
+
+	/// Like [`Node::text_eq`], but with whitespace runs on both sides collapsed to a single space and, if `ignore_case` is set,
+	/// compared case-insensitively.
+	pub fn text_eq_normalized(&self, expected: &str, ignore_case: bool) -> bool {
+		let actual = collapse_whitespace(&self.element.text().collect::<String>());
+		let expected = collapse_whitespace(expected);
+		if ignore_case { actual.trim().to_lowercase() == expected.trim().to_lowercase() } else { actual.trim() == expected.trim() }
+	}
+
+	/// Text of the descendants in document order, up to the first element matching `stop_selector`. Nothing from the stop element
+	/// or anything after it is included, even if it's nested deeper than the text before it.
+	pub fn text_until(&self, stop_selector: impl Into<Cow<'static, str>>) -> Result<Text> {
+		let stop_selector = stop_selector.into();
+		let stop = self
+			.document
+			.compile_selector(&stop_selector)
+			.map_err(|reason| self.make_error(reason, Operation::TextUntil { selector: stop_selector.clone() }))?;
+		let mut value = String::new();
+		for v in self.element.descendants().skip(1) {
+			match v.value() {
+				scraper::node::Node::Text(text) => value += text,
+				scraper::node::Node::Element(_) if stop.matches(&ElementRef::wrap(v).unwrap()) => break,
+				_ => (),
+			}
+		}
+		let operation = Operation::TextUntil { selector: stop_selector };
+		Ok(Text { document: self.document, source: Some(self), detached: Vec::new(), operation, value: self.document.trim_text(&value) })
+	}
+
+	pub fn text_multiline(&self) -> Text {
+		let mut value = String::new();
+		for v in self.element.descendants() {
+			match v.value() {
+				scraper::node::Node::Text(text) => value += &*text,
+				scraper::node::Node::Element(element) if element.name() == "br" => value += "\n",
+				_ => (),
+			}
+		}
+		let value = self.document.trim_multiline_text(&value);
+		Text { document: self.document, source: Some(self), detached: Vec::new(), operation: Operation::TextMultiline, value }
+	}
+
+	/// Text with whitespace runs, line breaks and non-breaking spaces collapsed to single spaces, regardless of the document's
+	/// [`TrimPolicy`]. See [`Text::normalized_with`] for other options.
+	pub fn text_collapsed(&self) -> Text {
+		let value = TextOptions::default().apply(&self.element.text().collect::<String>());
+		Text { document: self.document, source: Some(self), detached: Vec::new(), operation: Operation::TextCollapsed, value }
+	}
+
+	pub fn attr(&self, key: impl Into<Cow<'static, str>>) -> Result<Text> {
+		let key = key.into();
+		let value = match self.element.value().attr(&key) {
+			Some(value) => value.to_owned(),
+			None => return Err(self.make_error(Reason::NotFound, Operation::Attr { key })),
+		};
+		Ok(Text { document: self.document, source: Some(self), detached: Vec::new(), operation: Operation::Attr { key }, value })
+	}
+
+	/// Shorthand for `attr(key)?.parse()`, recorded as one operation that names both the attribute and the target type.
+	pub fn attr_parse<T>(&self, key: impl Into<Cow<'static, str>>) -> Result<T>
+	where
+		T: FromStr+'static,
+		<T as FromStr>::Err: fmt::Debug+fmt::Display+Send+Sync+'static,
+	{
+		let key = key.into();
+		let operation = || Operation::AttrParse { key: key.clone(), ty: type_name::<T>() };
+		let value = self.element.value().attr(&key).ok_or_else(|| self.make_error(Reason::NotFound, operation()))?;
+		value.parse().map_err(|inner| self.make_error(parse_reason::<T>(value, inner), operation()))
+	}
+
+	/// Like [`Node::attr`], but returns `None` if the attribute is missing, for attributes that are genuinely optional.
+	pub fn attr_opt(&self, key: impl Into<Cow<'static, str>>) -> Option<Text> {
+		let key = key.into();
+		let value = self.element.value().attr(&key)?.to_owned();
+		Some(Text { document: self.document, source: Some(self), detached: Vec::new(), operation: Operation::Attr { key }, value })
+	}
+
+	/// Lowercase tag name of this node, like `div`.
+	pub fn name(&self) -> &'a str {
+		self.element.value().name()
+	}
+
+	/// Whether the attribute is present, regardless of its value, as with boolean attributes like `disabled`.
+	pub fn has_attr(&self, key: &str) -> bool {
+		self.element.value().attr(key).is_some()
+	}
+
+	/// All attributes of this node as name and value pairs, in no particular order.
+	pub fn attrs(&self) -> impl Iterator<Item = (&'a str, &'a str)> {
+		self.element.value().attrs()
+	}
+
+	/// Value of the `data-{key}` attribute, so `data("user-id")` reads `data-user-id`.
+	pub fn data(&self, key: &str) -> Result<Text> {
+		self.attr(format!("data-{}", key))
+	}
+
+	/// All `data-*` attributes, keyed by the name after the `data-` prefix as written in the HTML, without the DOM's camel-casing.
+	pub fn dataset(&self) -> HashMap<&'a str, &'a str> {
+		self.attrs().filter_map(|(key, value)| Some((key.strip_prefix("data-")?, value))).collect()
+	}
+
+	/// Classes of this node, in the order they're written.
+	pub fn classes(&self) -> impl Iterator<Item = &'a str> {
+		self.element.value().classes()
+	}
+
+	pub fn has_class(&self, class: &str) -> bool {
+		self.classes().any(|candidate| candidate == class)
+	}
+
+	/// Fails with [`Reason::MissingClass`] unless this node has the class, for checking state classes like `accepted`.
+	pub fn require_class(&self, class: &str) -> Result<()> {
+		if self.has_class(class) {
+			Ok(())
+		} else {
+			Err(self.make_error(Reason::MissingClass(class.to_owned()), Operation::RequireClass { class: class.to_owned() }))
+		}
+	}
+
+	/// Splits a list-valued attribute like `class` or `rel` on `sep`, dropping empty tokens. Whitespace-separated lists should use
+	/// `' '`, which also splits on tabs and newlines. Returns an empty list if the attribute is missing.
+	pub fn attr_all(&self, key: &str, sep: char) -> Vec<String> {
+		let value = self.element.value().attr(key).unwrap_or("");
+		let tokens: Vec<&str> = if sep == ' ' { value.split_whitespace().collect() } else { value.split(sep).map(str::trim).collect() };
+		tokens.into_iter().filter(|token| !token.is_empty()).map(str::to_owned).collect()
+	}
+
+	/// Candidates of the `srcset` attribute as URL and optional descriptor pairs, e.g. `("large.jpg", Some("2x"))`. URLs that contain
+	/// commas are not supported.
+	pub fn srcset(&self) -> Vec<(String, Option<String>)> {
+		self.attr_all("srcset", ',')
+			.into_iter()
+			.map(|candidate| match candidate.split_once(char::is_whitespace) {
+				Some((url, descriptor)) => (url.to_owned(), Some(descriptor.trim().to_owned())),
+				None => (candidate, None),
+			})
+			.collect()
+	}
+
+	/// The `href` attribute resolved to an absolute URL, using the base set with [`Document::set_base`], the page's `<base href>`,
+	/// or the URL a fetched page was downloaded from, in that order. Already absolute links are returned as they are.
+	#[cfg(feature = "url")]
+	pub fn abs_href(&self) -> Result<Text> {
+		self.resolve_href(Operation::AbsHref)
+	}
+
+	/// The `href` attribute, resolved with the same base as [`Node::abs_href`], so links read through either method agree.
+	#[cfg(feature = "url")]
+	pub fn href(&self) -> Result<Text> {
+		self.resolve_href(Operation::Href)
+	}
+
+	#[cfg(feature = "url")]
+	fn resolve_href(&self, operation: Operation) -> Result<Text> {
+		let href = self.element.value().attr("href").ok_or_else(|| self.make_error(Reason::NotFound, operation.clone()))?;
+		let url = match self.document.base_url() {
+			Some(base) => base.join(href),
+			None => url::Url::parse(href),
+		};
+		let url = url.map_err(|inner| self.make_error(Reason::External(Arc::new(inner)), operation.clone()))?;
+		Ok(Text { document: self.document, source: Some(self), detached: Vec::new(), operation, value: url.to_string() })
+	}
+
+	/// Deserializes the contents of a `<script type="application/json">` or `<script type="application/ld+json">`. Other nodes fail
+	/// with a logic error rather than being parsed as JSON by accident.
+	#[cfg(feature = "json")]
+	pub fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
+		let element = self.element.value();
+		let kind = element.attr("type").map(|kind| kind.split(';').next().unwrap().trim().to_ascii_lowercase());
+		if element.name() != "script" || !matches!(kind.as_deref(), Some("application/json") | Some("application/ld+json")) {
+			return Err(self.make_error(Reason::Logic("expected a JSON <script>"), Operation::Json));
+		}
+		let json = self.element.text().collect::<String>();
+		serde_json::from_str(&json).map_err(|inner| self.make_error(Reason::External(Arc::new(inner)), Operation::Json))
+	}
+
+	/// Converts the contents to Markdown, keeping bold (`<b>`, `<strong>`), italics (`<i>`, `<em>`), links, line breaks, paragraphs
+	/// and lists. Any other tag is dropped but its text is kept. This is a best-effort helper, not a complete converter: tables,
+	/// headings, images and code are rendered as plain text.
+	pub fn markdown(&self) -> String {
+		let mut markdown = String::new();
+		write_markdown(self.element, &mut markdown, 0);
+		squash_blank_lines(&markdown)
+	}
+
+	/// Text laid out roughly like a browser renders it, for descriptions and other prose. Block elements like `<div>` and `<tr>`
+	/// start a new line, paragraphs and headings are separated by a blank line, list items get a `•` or their number, and table
+	/// cells are separated by tabs. Whitespace within a line is collapsed, and scripts and styles are skipped.
+	pub fn text_blocks(&self) -> Text {
+		let mut text = String::new();
+		write_text_blocks(self.element, &mut text, 0);
+		Text { document: self.document, source: Some(self), detached: Vec::new(), operation: Operation::TextBlocks, value: squash_blank_lines(&text) }
+	}
+
+	/// Serialized HTML of this node, including its own tag.
+	pub fn outer_html(&self) -> String {
+		serialize(self.element, &[])
+	}
+
+	/// Length of [`Node::outer_html`] in bytes. There's no way to know it without serializing, but only the length is cached in the
+	/// document, so checking the size of the same node again is free.
+	pub fn html_len(&self) -> usize {
+		*self.document.html_len_cache.query(&self.element.id(), |_| self.outer_html().len())
+	}
+
+	/// Serializes this node like [`Node::outer_html`], but with the values of the listed attributes replaced by `***`, so it can be
+	/// logged without leaking tokens or personal data. Entries ending with `*` match by prefix, e.g. `data-*` masks all data
+	/// attributes. Attribute names and the rest of the markup are kept.
+	pub fn outer_html_redacted(&self, redact: &[&str]) -> String {
+		let mut html = String::new();
+		write_redacted_html(self.element, redact, &mut html);
+		html
+	}
+
+	/// Serializes this node with attributes sorted by name, whitespace-only text dropped and other whitespace runs collapsed to a
+	/// single space, for snapshot assertions that shouldn't break on formatting changes. Lossy for whitespace-sensitive elements such
+	/// as `<pre>`.
+	pub fn normalized_html(&self) -> String {
+		let mut html = String::new();
+		write_normalized_html(self.element, &mut html);
+		html
+	}
+
+	/// Serializes this node and parses it again as a standalone document, so it can outlive the original one. Selectors on the new
+	/// document only see this subtree, and errors no longer include the original operation chain. This is a full reparse, so elements
+	/// that are only valid in context (like a lone `<td>`) get fixed up the same way a browser would.
+	pub fn to_document(&self) -> Document {
+		Document::new(&self.element.html())
+	}
+
+	/// Copies this node out of the document, keeping its breadcrumb for error reporting. Unlike [`Node::to_document`], the result
+	/// can be sent to other threads; it's rebuilt into a document each time it's used.
+	pub fn to_owned_node(&self) -> NodeOwned {
+		NodeOwned::new(self)
+	}
+
+	/// Element siblings preceding this node, in document order.
+	pub fn siblings_before(&self) -> Collection {
+		let mut siblings: Vec<_> = self.element.prev_siblings().filter_map(ElementRef::wrap).collect();
+		siblings.reverse();
+		Collection {
+			document: self.document,
+			source: Some(self),
+			operation: Operation::SiblingBefore { index: 0 },
+			iterator: Box::new(siblings.into_iter()),
+			index: 0,
+		}
+	}
+
+	/// Element siblings following this node, in document order.
+	pub fn siblings_after(&self) -> Collection {
+		Collection {
+			document: self.document,
+			source: Some(self),
+			operation: Operation::SiblingAfter { index: 0 },
+			iterator: Box::new(self.element.next_siblings().filter_map(ElementRef::wrap)),
+			index: 0,
+		}
+	}
+
+	/// Element siblings of this node, excluding itself, in document order.
+	pub fn siblings(&self) -> Collection {
+		let id = self.element.id();
+		let siblings = self.element.parent().into_iter().flat_map(|parent| parent.children()).filter(move |node| node.id() != id);
+		Collection {
+			document: self.document,
+			source: Some(self),
+			operation: Operation::Sibling { index: 0 },
+			iterator: Box::new(siblings.filter_map(ElementRef::wrap)),
+			index: 0,
+		}
+	}
+
+	/// The node right after this one. Fails with `ExpectedElement` if it's text or a comment, which includes the whitespace between
+	/// tags; use [`Node::next_sibling_element`] to skip over those.
+	pub fn next_sibling(&self) -> Result<Node> {
+		self.sibling(self.element.next_sibling(), Operation::NextSibling)
+	}
+
+	/// The node right before this one. See [`Node::next_sibling`].
+	pub fn prev_sibling(&self) -> Result<Node> {
+		self.sibling(self.element.prev_sibling(), Operation::PrevSibling)
+	}
+
+	/// The first element after this one, e.g. the `<dd>` following a `<dt>`.
+	pub fn next_sibling_element(&self) -> Result<Node> {
+		self.sibling(self.element.next_siblings().find(|node| node.value().is_element()), Operation::NextSibling)
+	}
+
+	/// The first element before this one.
+	pub fn prev_sibling_element(&self) -> Result<Node> {
+		self.sibling(self.element.prev_siblings().find(|node| node.value().is_element()), Operation::PrevSibling)
+	}
+
+	fn sibling(&self, sibling: Option<ego_tree::NodeRef<'a, scraper::Node>>, operation: Operation) -> Result<Node> {
+		let sibling = sibling.ok_or_else(|| self.make_error(Reason::NotFound, operation.clone()))?;
+		let element = ElementRef::wrap(sibling).ok_or_else(|| self.make_error(Reason::ExpectedElement, operation.clone()))?;
+		Ok(Node { document: self.document, source: Some(self), operation, element })
+	}
+
+	/// Runs an extraction helper on this node, rerooting its errors here. Operations and snapshots from the document down to this
+	/// node's parent are dropped, so the outermost snapshot is this node's HTML instead of the whole page, and the breadcrumb only
+	/// shows what happened inside `f`. Errors that don't come from this node, including ones from other documents that happen to
+	/// share its breadcrumb, are returned unchanged.
+	pub fn extract<T>(&self, f: impl FnOnce(&Node) -> Result<T>) -> Result<T> {
+		f(self).map_err(|mut error| {
+			let prefix = self.collect_operations();
+			let depth = prefix.len();
+			let snapshots: Vec<String> = self.collect_snapshots().into_iter().map(|snapshot| self.document.limit_snapshot(snapshot)).collect();
+			if error.snapshots.len() >= snapshots.len()
+				&& error.snapshots[..snapshots.len()] == snapshots[..]
+				&& error.operations.len() >= depth
+				&& error.operations[..depth].iter().map(Operation::name).eq(prefix.iter().map(Operation::name))
+			{
+				error.operations.drain(..depth - 1);
+				error.snapshots.drain(..depth);
+			}
+			error
+		})
+	}
+
+	/// Every `<a href>` inside this node. See [`Document::links`].
+	pub fn links(&self) -> impl Iterator<Item = (Node, Text)> {
+		links(self.find_all("a[href]").unwrap())
+	}
+
+	/// Collects the controls of this `<form>` with their default values, e.g. to read a hidden CSRF token and submit the form.
+	pub fn as_form(&self) -> Result<Form> {
+		Form::new(self)
+	}
+
+	/// Lays out this `<table>` as a grid with spans resolved, for reading cells by position or header instead of walking rows and
+	/// children by hand.
+	pub fn as_table(&self) -> Result<Table> {
+		Table::new(self)
+	}
+
+	/// Reads the microdata item on this `itemscope` element, including nested items.
+	pub fn microdata(&self) -> Result<Item> {
+		microdata::from_node(self)
+	}
+
+	/// Text of the `<th>` above this cell. The header row is the first `<tr>` of the enclosing table that contains any `<th>`, and the
+	/// column is this cell's position among its element siblings, so colspans are not taken into account.
+	pub fn cell_header(&self) -> Result<Text> {
+		let column = self.element.prev_siblings().filter(|node| node.value().is_element()).count();
+		let header = self
+			.element
+			.ancestors()
+			.filter_map(ElementRef::wrap)
+			.find(|element| element.value().name() == "table")
+			.and_then(|table| {
+				table.descendants().filter_map(ElementRef::wrap).find(|row| {
+					row.value().name() == "tr" && row.children().filter_map(ElementRef::wrap).any(|cell| cell.value().name() == "th")
+				})
+			})
+			.and_then(|row| row.children().filter_map(ElementRef::wrap).nth(column))
+			.ok_or_else(|| self.make_error(Reason::NotFound, Operation::CellHeader))?;
+		let value = self.document.trim_text(&header.text().collect::<String>());
+		Ok(Text { document: self.document, source: Some(self), detached: Vec::new(), operation: Operation::CellHeader, value })
+	}
+}
+impl<'a> Context for Node<'a> {
+	fn get_document(&self) -> &Document {
+		self.document
+	}
+
+	fn get_source(&self) -> Option<&Node> {
+		self.source
+	}
+
+	fn get_operation(&self) -> Option<Operation> {
+		Some(self.operation.clone())
+	}
+
+	fn get_as_source(&self) -> Option<&Node> {
+		Some(self)
+	}
+}
+impl<'a> Find for Node<'a> {
+	fn find_all(&self, selector: impl Into<Cow<'static, str>>) -> Result<Collection> {
+		let selector = selector.into();
+		let compiled = self
+			.document
+			.compile_selector(&selector)
+			.map_err(|reason| self.make_error(reason, Operation::FindAll { selector: selector.clone(), index: 0 }))?;
+		Ok(Collection {
+			document: self.document,
+			source: Some(self),
+			iterator: Box::new(compiled.select(self.element)),
+			operation: Operation::FindAll { selector, index: 0 },
+			index: 0,
+		})
+	}
+}
+
+impl<'a> Collection<'a> {
+	/// Returns the next match without consuming it. This clones the underlying iterator and advances the copy, so it costs as much
+	/// as the search for the next match; looking at the same element twice repeats that work.
+	pub fn peek(&self) -> Option<Node<'a>> {
+		self.iterator.clone_box().next().map(|element| Node {
+			document: self.document,
+			source: self.source,
+			operation: self.operation.at(self.index),
+			element,
+		})
+	}
+
+	/// Number of matches not consumed yet. Like [`Collection::peek`], this runs the rest of the search on a copy of the iterator.
+	pub fn count_remaining(&self) -> usize {
+		self.iterator.clone_box().count()
+	}
+
+	/// Whether there are no matches left, e.g. for sections that are optional on some pages. Only searches up to the first match.
+	pub fn is_empty(&self) -> bool {
+		self.iterator.clone_box().next().is_none()
+	}
+
+	/// The remaining matches as a `Vec`, for when they're needed more than once or by index.
+	pub fn collect_nodes(self) -> Vec<Node<'a>> {
+		self.collect()
+	}
+
+	/// Groups consecutive matches into rows of `n`, for flat lists that are laid out as a grid. The last chunk is shorter if the
+	/// number of matches is not divisible by `n`. Panics if `n` is zero.
+	pub fn chunks(mut self, n: usize) -> impl Iterator<Item = Vec<Node<'a>>> {
+		assert_ne!(n, 0, "chunk size must be non-zero");
+		std::iter::from_fn(move || {
+			let chunk: Vec<_> = self.by_ref().take(n).collect();
+			if chunk.is_empty() { None } else { Some(chunk) }
+		})
+	}
+
+	/// Pairs matches with the matches of `other` by position, for parallel columns like labels and values. Like [`Iterator::zip`], it
+	/// stops at the end of the shorter collection, so unmatched trailing elements of the longer one are silently dropped.
+	pub fn zip_nodes(self, other: Collection<'a>) -> impl Iterator<Item = (Node<'a>, Node<'a>)> {
+		self.zip(other)
+	}
+
+	/// Pairs each matched row with the text of its single descendant matching `selector`. Rows where the field is missing or
+	/// ambiguous yield an error with the row's context. Errors from the returned text have the same operations and snapshots as if
+	/// it had been read with `row.find(selector)?.text()`.
+	pub fn with_field(self, selector: impl Into<Cow<'static, str>>) -> impl Iterator<Item = Result<(Node<'a>, Text<'a>)>> {
+		let selector = selector.into();
+		let document = self.document;
+		let source = self.source;
+		self.map(move |row| {
+			let field = row.find(selector.clone())?;
+			let Text { operation, value, .. } = field.text();
+			// The field borrows the row, so its element is looked up again to get one that lives as long as the document.
+			let element = document.tree.tree.get(field.element.id()).and_then(ElementRef::wrap).unwrap();
+			let detached = vec![(row.operation.clone(), row.element), (field.operation.clone(), element)];
+			Ok((row, Text { document, source, detached, operation, value }))
+		})
+	}
+
+	/// First value that `f` maps a matched node to.
+	pub fn find_map_node<T>(&mut self, mut f: impl FnMut(&Node) -> Option<T>) -> Option<T> {
+		self.find_map(|node| f(&node))
+	}
+
+	/// Like [`Collection::find_map_node`], but stops at the first error returned by `f`, keeping the context of the node that
+	/// caused it.
+	pub fn try_find_map_node<T>(&mut self, mut f: impl FnMut(&Node) -> Result<Option<T>>) -> Result<Option<T>> {
+		for node in self {
+			if let Some(value) = f(&node)? {
+				return Ok(Some(value));
+			}
+		}
+		Ok(None)
+	}
+
+	/// Maps every match with `f`, stopping at the first error. Errors keep the context of the node they came from.
+	pub fn map_collect<T>(self, f: impl FnMut(Node<'a>) -> Result<T>) -> Result<Vec<T>> {
+		self.map(f).collect()
+	}
+
+	/// Like [`Collection::map_collect`], but skips matches for which `f` returns `None`.
+	pub fn try_filter_map<T>(self, mut f: impl FnMut(Node<'a>) -> Result<Option<T>>) -> Result<Vec<T>> {
+		let mut values = Vec::new();
+		for node in self {
+			values.extend(f(node)?);
+		}
+		Ok(values)
+	}
+
+	/// The next match, or a `NotFound` error with the selector and position if there are none left.
+	pub fn first(mut self) -> Result<Node<'a>> {
+		let index = self.index;
+		self.next().ok_or_else(|| self.make_error(Reason::NotFound, index))
+	}
+
+	/// The last match, or a `NotFound` error if there are none left.
+	pub fn last(mut self) -> Result<Node<'a>> {
+		let index = self.index;
+		self.by_ref().last().ok_or_else(|| self.make_error(Reason::NotFound, index))
+	}
+
+	/// The match `index` positions ahead, counting from zero, or a `NotFound` error with the selector and position if there are
+	/// fewer matches left.
+	pub fn nth(mut self, index: usize) -> Result<Node<'a>> {
+		let position = self.index + index;
+		self.by_ref().nth(index).ok_or_else(|| self.make_error(Reason::NotFound, position))
+	}
+
+	fn make_error(&self, reason: Reason, index: usize) -> Error {
+		match self.source {
+			Some(source) => source.make_error(reason, self.operation.at(index)),
+			None => self.document.make_error(reason, self.operation.at(index)),
+		}
+	}
+
+	/// Builds a lookup table from rows, parsing the text under `key_selector` as the key and under `value_selector` as the value.
+	/// A key that appears twice is reported as an error rather than silently overwritten.
+	pub fn into_map<K, V>(self, key_selector: impl Into<Cow<'static, str>>, value_selector: impl Into<Cow<'static, str>>) -> Result<HashMap<K, V>>
+	where
+		K: FromStr+Hash+Eq+'static,
+		<K as FromStr>::Err: fmt::Debug+fmt::Display+Send+Sync+'static,
+		V: FromStr+'static,
+		<V as FromStr>::Err: fmt::Debug+fmt::Display+Send+Sync+'static,
+	{
+		let key_selector = key_selector.into();
+		let value_selector = value_selector.into();
+		let mut map = HashMap::new();
+		for row in self {
+			let key_node = row.find(key_selector.clone())?;
+			let key_text = key_node.text();
+			let value = row.find(value_selector.clone())?.text().parse()?;
+			if map.insert(key_text.parse()?, value).is_some() {
+				return Err(key_text.make_error(Reason::Logic("duplicate key"), Operation::MapKey));
+			}
+		}
+		Ok(map)
+	}
+}
+impl<'a> Iterator for Collection<'a> {
+	type Item = Node<'a>;
+
+	fn next(&mut self) -> Option<Node<'a>> {
+		self.iterator.next().map(|element| {
+			let node = Node {
+				document: self.document,
+				operation: self.operation.at(self.index),
+				source: self.source,
+				element,
+			};
+			self.index += 1;
+			node
+		})
+	}
+}
+
+impl<'a> Text<'a> {
+	pub fn string(&self) -> String {
+		self.value.clone()
+	}
+
+	pub fn as_str(&self) -> &str {
+		&self.value
+	}
+
+	/// The text with whitespace runs, line breaks and non-breaking spaces collapsed to single spaces and the ends trimmed.
+	pub fn normalized(&self) -> Text<'a> {
+		self.normalized_with(&TextOptions::default())
+	}
+
+	pub fn normalized_with(&self, options: &TextOptions) -> Text<'a> {
+		Text {
+			document: self.document,
+			source: self.source,
+			detached: self.detached.clone(),
+			operation: self.operation.clone(),
+			value: options.apply(&self.value),
+		}
+	}
+
+	/// Fails with "empty text" if the text is empty or only whitespace, so a blank field is reported with the selector that produced
+	/// it instead of silently passing through.
+	pub fn ensure_nonempty(self) -> Result<Text<'a>> {
+		if self.value.trim().is_empty() { Err(self.make_error(Reason::Logic("empty text"), Operation::EnsureNonempty)) } else { Ok(self) }
+	}
+
+	/// Parses the text with [`FromStr`]. If an integer doesn't fit in the target type, the error names the value and the type, e.g.
+	/// `value "123456789012345678901234567890" too large for u64`, instead of just "number too large to fit in target type".
+	pub fn parse<T>(&self) -> Result<T>
+	where
+		T: FromStr+'static,
+		<T as FromStr>::Err: fmt::Debug+fmt::Display+Send+Sync+'static,
+	{
+		self.value.parse().map_err(|inner| self.parse_error::<T>(&self.value, inner))
+	}
+
+	/// Like [`Text::parse`], but trims whitespace first, including non-breaking spaces, even if the document's
+	/// [`TrimPolicy`] kept it.
+	pub fn parse_trimmed<T>(&self) -> Result<T>
+	where
+		T: FromStr+'static,
+		<T as FromStr>::Err: fmt::Debug+fmt::Display+Send+Sync+'static,
+	{
+		self.parse_part(&self.value)
+	}
+
+	/// Parses the first number in the text, ignoring grouping separators and whatever is around it, so "1,234 pts" parses as 1234.
+	/// See [`NumberFormat`] for the details.
+	pub fn parse_number<T>(&self) -> Result<T>
+	where
+		T: FromStr+'static,
+		<T as FromStr>::Err: fmt::Debug+fmt::Display+Send+Sync+'static,
+	{
+		self.parse_number_with(&NumberFormat::default())
+	}
+
+	/// Like [`Text::parse_number`], for numbers written differently, e.g. with a decimal comma.
+	pub fn parse_number_with<T>(&self, format: &NumberFormat) -> Result<T>
+	where
+		T: FromStr+'static,
+		<T as FromStr>::Err: fmt::Debug+fmt::Display+Send+Sync+'static,
+	{
+		let number =
+			format.extract(&self.value).ok_or_else(|| self.make_error(external("expected number"), Operation::Parse { ty: type_name::<T>() }))?;
+		self.parse_part(&number)
+	}
+
+	/// Parses the text, falling back to `default` if it's empty or malformed. The error is discarded on purpose, so only use this for
+	/// fields where a bad value really shouldn't fail the scrape.
+	pub fn parse_or<T: FromStr>(&self, default: T) -> T {
+		self.value.parse().unwrap_or(default)
+	}
+
+	/// Parses "87%" as `0.87`.
+	pub fn parse_percentage(&self) -> Result<f64> {
+		let number = self.value.trim().strip_suffix('%').ok_or_else(|| self.make_error(external("expected '%'"), Operation::Parse { ty: "f64" }))?;
+		Ok(self.parse_part::<f64>(number)? / 100.)
+	}
+
+	/// Parses "4.5/5" as `(4.5, 5.)`.
+	pub fn parse_ratio(&self) -> Result<(f64, f64)> {
+		let (numerator, denominator) = self
+			.value
+			.trim()
+			.split_once('/')
+			.ok_or_else(|| self.make_error(external("expected '/'"), Operation::Parse { ty: type_name::<(f64, f64)>() }))?;
+		Ok((self.parse_part(numerator)?, self.parse_part(denominator)?))
+	}
+
+	/// Parses "37.7749, -122.4194" as a latitude and longitude pair.
+	pub fn parse_coords(&self) -> Result<(f64, f64)> {
+		let (latitude, longitude) = self
+			.value
+			.trim()
+			.split_once(',')
+			.ok_or_else(|| self.make_error(external("expected ','"), Operation::Parse { ty: type_name::<(f64, f64)>() }))?;
+		Ok((self.parse_part(latitude)?, self.parse_part(longitude)?))
+	}
+
+	/// Parses the text as a URL, resolving it against the document's base like [`Node::abs_href`] if it's relative.
+	#[cfg(feature = "url")]
+	pub fn url(&self) -> Result<url::Url> {
+		let url = match self.document.base_url() {
+			Some(base) => base.join(self.value.trim()),
+			None => url::Url::parse(self.value.trim()),
+		};
+		url.map_err(|inner| self.make_error(Reason::External(Arc::new(inner)), Operation::Url))
+	}
+
+	/// Parses the text with a `chrono` format string like `"%d %b %Y, %H:%M"`. Formats without a time, like `"%Y-%m-%d"`, are
+	/// accepted too and give midnight.
+	#[cfg(feature = "chrono")]
+	pub fn parse_datetime(&self, format: &str) -> Result<chrono::NaiveDateTime> {
+		parse_naive_datetime(self.value.trim(), format)
+			.map_err(|inner| self.make_error(Reason::External(Arc::new(inner)), Operation::ParseDatetime { formats: vec![format.to_owned()] }))
+	}
+
+	/// Like [`Text::parse_datetime`], but tries each format in order, for sites that write recent and older dates differently.
+	#[cfg(feature = "chrono")]
+	pub fn parse_datetime_any(&self, formats: &[&str]) -> Result<chrono::NaiveDateTime> {
+		let value = self.value.trim();
+		formats.iter().find_map(|format| parse_naive_datetime(value, format).ok()).ok_or_else(|| {
+			let formats = formats.iter().map(|format| (*format).to_owned()).collect();
+			self.make_error(external(format!("{:?} doesn't match any format", value)), Operation::ParseDatetime { formats })
+		})
+	}
+
+	/// Captures of the first match of `pattern` in the text. Compiled patterns are cached in the document, and a text that doesn't
+	/// match fails with the text in the message.
+	#[cfg(feature = "regex")]
+	pub fn regex(&self, pattern: &str) -> Result<regex::Captures> {
+		let operation = || Operation::Regex { pattern: pattern.to_owned() };
+		let regex = self.document.compile_regex(pattern).map_err(|reason| self.make_error(reason, operation()))?;
+		let no_match = || self.make_error(external(format!("{:?} doesn't match", self.value)), operation());
+		regex.captures(&self.value).ok_or_else(no_match)
+	}
+
+	/// Parses the first capture group of `pattern`, or the whole match if there are no groups, e.g. `r"(\d+) answers"`.
+	#[cfg(feature = "regex")]
+	pub fn regex_parse<T>(&self, pattern: &str) -> Result<T>
+	where
+		T: FromStr+'static,
+		<T as FromStr>::Err: fmt::Debug+fmt::Display+Send+Sync+'static,
+	{
+		let captures = self.regex(pattern)?;
+		let group = if captures.len() > 1 { 1 } else { 0 };
+		self.parse_part(captures.get(group).map_or("", |part| part.as_str()))
+	}
+
+	/// Splits "Key: Value" style text on the first `sep`, trimming both halves.
+	pub fn split_once(&self, sep: char) -> Option<(&str, &str)> {
+		self.value.split_once(sep).map(|(key, value)| (key.trim(), value.trim()))
+	}
+
+	/// Splits the text like [`Text::split_once`] and parses the value half.
+	pub fn parse_kv<T>(&self, sep: char) -> Result<(String, T)>
+	where
+		T: FromStr+'static,
+		<T as FromStr>::Err: fmt::Debug+fmt::Display+Send+Sync+'static,
+	{
+		let (key, value) =
+			self.split_once(sep).ok_or_else(|| self.make_error(external(format!("expected '{}'", sep)), Operation::Parse { ty: type_name::<T>() }))?;
+		Ok((key.to_owned(), self.parse_part(value)?))
+	}
+
+	/// Parses short relative durations: a number followed by a unit, optionally followed by "ago", like "2h", "15 min" or "3 days
+	/// ago". Supported units are seconds (`s`, `sec`, `second`), minutes (`m`, `min`, `minute`), hours (`h`, `hour`), days (`d`,
+	/// `day`) and weeks (`w`, `week`), in any case and with or without a plural `s`.
+	#[cfg(feature = "duration")]
+	pub fn parse_duration(&self) -> Result<std::time::Duration> {
+		let value = self.value.trim();
+		let value = value.strip_suffix("ago").map_or(value, str::trim_end);
+		let (number, unit) = value.split_at(value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len()));
+		let unit = unit.trim().to_ascii_lowercase();
+		let unit_seconds = match unit.strip_suffix('s').filter(|unit| unit.len() > 1).unwrap_or(&unit) {
+			"s" | "sec" | "second" => 1,
+			"m" | "min" | "minute" => 60,
+			"h" | "hour" => 60 * 60,
+			"d" | "day" => 24 * 60 * 60,
+			"w" | "week" => 7 * 24 * 60 * 60,
+			_ => return Err(self.make_error(external("unsupported duration format"), Operation::Parse { ty: type_name::<std::time::Duration>() })),
+		};
+		let seconds = self
+			.parse_part::<u64>(number)?
+			.checked_mul(unit_seconds)
+			.ok_or_else(|| self.make_error(external("duration too long"), Operation::Parse { ty: type_name::<std::time::Duration>() }))?;
+		Ok(std::time::Duration::from_secs(seconds))
+	}
+
+	/// Parses prices like "$1,299.00", "€ 49,90" or "12.50 PLN" into the amount and the currency symbol or code written before or
+	/// after it, if any. The currency is returned as written, without converting symbols to ISO codes. Spaces and apostrophes are
+	/// treated as grouping separators. When both `.` and `,` appear, the last one is the decimal separator. When only one of them
+	/// appears, it's a grouping separator if it occurs more than once or is followed by exactly three digits, so "1.299" is read as
+	/// 1299 and not 1.299.
+	#[cfg(feature = "money")]
+	pub fn parse_money(&self) -> Result<(f64, Option<String>)> {
+		let value = self.value.trim();
+		let start = value.find(|c: char| c.is_ascii_digit() || c == '-' || c == '+');
+		let end = value.rfind(|c: char| c.is_ascii_digit());
+		let (start, end) = match (start, end) {
+			(Some(start), Some(end)) if start <= end => (start, end + 1),
+			_ => return Err(self.make_error(external("expected amount"), Operation::Parse { ty: "f64" })),
+		};
+		let currency = match (value[..start].trim(), value[end..].trim()) {
+			("", "") => None,
+			(prefix, "") => Some(prefix.to_owned()),
+			("", suffix) => Some(suffix.to_owned()),
+			_ => return Err(self.make_error(external("currency on both sides of amount"), Operation::Parse { ty: "f64" })),
+		};
+		let amount: String = value[start..end].chars().filter(|c| !c.is_whitespace() && *c != '\'').collect();
+		let decimal = match (amount.rfind('.'), amount.rfind(',')) {
+			(Some(dot), Some(comma)) => Some(if dot > comma { '.' } else { ',' }),
+			(Some(last), None) | (None, Some(last)) => {
+				let separator = amount[last..].chars().next().unwrap();
+				let grouping = amount.matches(separator).count() > 1 || amount[last + 1..].len() == 3;
+				if grouping { None } else { Some(separator) }
+			},
+			(None, None) => None,
+		};
+		let amount: String = amount
+			.chars()
+			.filter(|c| !matches!(c, '.' | ',') || Some(*c) == decimal)
+			.map(|c| if Some(c) == decimal { '.' } else { c })
+			.collect();
+		Ok((self.parse_part(&amount)?, currency))
+	}
+
+	fn parse_part<T>(&self, part: &str) -> Result<T>
+	where
+		T: FromStr+'static,
+		<T as FromStr>::Err: fmt::Debug+fmt::Display+Send+Sync+'static,
+	{
+		part.trim().parse().map_err(|inner| self.parse_error::<T>(part.trim(), inner))
+	}
+
+	fn parse_error<T>(&self, input: &str, inner: <T as FromStr>::Err) -> Error
+	where
+		T: FromStr+'static,
+		<T as FromStr>::Err: fmt::Debug+fmt::Display+Send+Sync+'static,
+	{
+		self.make_error(parse_reason::<T>(input, inner), Operation::Parse { ty: type_name::<T>() })
+	}
+
+	/// Like [`Text::parse`], but panics with the full error report instead of returning it. Meant for tests and examples only.
+	#[cfg(feature = "test-helpers")]
+	#[track_caller]
+	pub fn expect_parse<T>(&self) -> T
+	where
+		T: FromStr+'static,
+		<T as FromStr>::Err: fmt::Debug+fmt::Display+Send+Sync+'static,
+	{
+		self.parse().unwrap_or_else(|e| panic!("{}", e.report()))
+	}
+
+	pub fn map<T, E: fmt::Debug+fmt::Display+Send+Sync+'static>(&self, f: impl FnOnce(&str) -> std::result::Result<T, E>) -> Result<T> {
+		f(&self.value).map_err(|inner| self.make_error(external(inner), Operation::External))
+	}
+
+	/// Canonical composition (NFC). Merges combining characters into precomposed ones, but leaves compatibility characters such as
+	/// full-width digits or ligatures untouched.
+	#[cfg(feature = "unicode")]
+	pub fn normalized_nfc(&self) -> String {
+		use unicode_normalization::UnicodeNormalization;
+		self.value.nfc().collect()
+	}
+
+	/// Compatibility composition (NFKC). Like NFC, but also folds compatibility characters, so "１２３" becomes "123" and "ﬁ" becomes
+	/// "fi". Use this before parsing numbers; it is lossy, so prefer NFC for text that is displayed back to users.
+	#[cfg(feature = "unicode")]
+	pub fn normalized_nfkc(&self) -> String {
+		use unicode_normalization::UnicodeNormalization;
+		self.value.nfkc().collect()
+	}
+
+	/// Removes accents and other combining marks, so "Café" becomes "Cafe". Characters that don't decompose, like "ø" or "ł", are
+	/// kept as they are.
+	#[cfg(feature = "unicode")]
+	pub fn without_diacritics(&self) -> String {
+		use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+		self.value.nfkd().filter(|c| !is_combining_mark(*c)).nfc().collect()
+	}
+
+	/// Lowercase identifier made from the text, e.g. "Hello, World!" becomes "hello-world". Runs of whitespace, hyphens and
+	/// underscores turn into a single hyphen and all other non-alphanumeric characters are removed. With the `unicode` feature,
+	/// diacritics are stripped first, so "Café" becomes "cafe"; without it, accented letters are kept.
+	pub fn slug(&self) -> String {
+		#[cfg(feature = "unicode")]
+		let value = self.without_diacritics();
+		#[cfg(not(feature = "unicode"))]
+		let value = self.value.as_str();
+		let mut slug = String::new();
+		for word in value.split(|c: char| c.is_whitespace() || c == '-' || c == '_') {
+			let word: String = word.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect();
+			if !word.is_empty() {
+				if !slug.is_empty() {
+					slug.push('-');
+				}
+				slug += &word;
+			}
+		}
+		slug
+	}
+}
+impl<'a> Context for Text<'a> {
+	fn get_document(&self) -> &Document {
+		self.document
 	}
 
 	fn get_source(&self) -> Option<&Node> {
-		Some(self.source)
+		self.source
 	}
 
 	fn get_operation(&self) -> Option<Operation> {
@@ -306,6 +2008,19 @@ impl<'a> Context for Text<'a> {
 	fn get_as_source(&self) -> Option<&Node> {
 		None
 	}
+
+	fn collect_operations(&self) -> Vec<Operation> {
+		let mut ops = self.source.map_or(Vec::new(), Context::collect_operations);
+		ops.extend(self.detached.iter().map(|(operation, _)| operation.clone()));
+		ops.push(self.operation.clone());
+		ops
+	}
+
+	fn collect_snapshots(&self) -> Vec<String> {
+		let mut sss = self.source.map_or_else(|| vec![self.document.root_snapshot()], Context::collect_snapshots);
+		sss.extend(self.detached.iter().map(|(_, element)| self.document.snapshot_html(*element)));
+		sss
+	}
 }
 impl<'a> PartialEq<&str> for Text<'a> {
 	fn eq(&self, other: &&str) -> bool {
@@ -315,12 +2030,12 @@ impl<'a> PartialEq<&str> for Text<'a> {
 
 impl fmt::Debug for Document {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "{}", self.tree.root_element().html())
+		write!(f, "{}", serialize(self.tree.root_element(), &[]))
 	}
 }
 impl fmt::Debug for Node<'_> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "{}", self.element.html())
+		write!(f, "{}", self.outer_html())
 	}
 }
 impl fmt::Debug for Text<'_> {
@@ -328,6 +2043,185 @@ impl fmt::Debug for Text<'_> {
 		write!(f, "{:?}", self.as_str())
 	}
 }
+impl fmt::Debug for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if f.alternate() {
+			let limit = f.precision().unwrap_or(2000);
+			let snapshot = self.snapshots.last().map_or("", |snapshot| truncate_chars(snapshot, limit));
+			let ellipsis = if self.snapshots.last().is_some_and(|last| last.len() > snapshot.len()) { "..." } else { "" };
+			f.debug_struct("Error")
+				.field("reason", &format_args!("{:#?}", self.reason))
+				.field("operations", &format_args!("{:#?}", self.operations))
+				.field("snapshot", &format_args!("{}{}", snapshot, ellipsis))
+				.field("backtrace", &format_args!("{:#?}", self.backtrace))
+				.finish()
+		} else {
+			f.debug_struct("Error")
+				.field("reason", &self.reason)
+				.field("operations", &self.operations)
+				.field("snapshots", &self.snapshots)
+				.field("backtrace", &self.backtrace)
+				.finish()
+		}
+	}
+}
+
+fn backtrace_by_default() -> bool {
+	static ENABLED: OnceLock<bool> = OnceLock::new();
+	*ENABLED.get_or_init(|| std::env::var_os("RUST_BACKTRACE").map_or(true, |value| value != "0"))
+}
+
+#[cfg(not(test))]
+use serialize_html as serialize;
+#[cfg(test)]
+use tests::counting_serialize as serialize;
+
+/// Serializes `element`, with the values of the `redact` attributes masked like in [`Node::outer_html_redacted`].
+fn serialize_html(element: ElementRef, redact: &[&str]) -> String {
+	if redact.is_empty() {
+		element.html()
+	} else {
+		let mut html = String::new();
+		write_redacted_html(element, redact, &mut html);
+		html
+	}
+}
+
+fn truncate_chars(text: &str, limit: usize) -> &str {
+	text.char_indices().nth(limit).map_or(text, |(end, _)| &text[..end])
+}
+
+impl Operation {
+	fn at(&self, index: usize) -> Operation {
+		match *self {
+			Operation::FindAll { ref selector, .. } => Operation::FindAll { selector: selector.clone(), index },
+			Operation::XPathAll { ref expr, .. } => Operation::XPathAll { expr: expr.clone(), index },
+			Operation::FindAllWhere { ref selector, ref label, .. } => {
+				Operation::FindAllWhere { selector: selector.clone(), label: label.clone(), index }
+			},
+			Operation::FindHaving { ref outer, ref inner, .. } => Operation::FindHaving { outer: outer.clone(), inner: inner.clone(), index },
+			Operation::SiblingBefore { .. } => Operation::SiblingBefore { index },
+			Operation::SiblingAfter { .. } => Operation::SiblingAfter { index },
+			Operation::Sibling { .. } => Operation::Sibling { index },
+			Operation::ChildElement { .. } => Operation::ChildElement { index },
+			ref operation => operation.clone(),
+		}
+	}
+
+	fn name(&self) -> String {
+		match self {
+			Operation::Find { selector } => format!("find:{}", selector),
+			Operation::FindAll { selector, .. } => format!("find_all:{}", selector),
+			Operation::FindFirst { selector } => format!("find_first:{}", selector),
+			Operation::FindNth { selector, index } => format!("find_nth:{}:{}", selector, index),
+			Operation::FindAny { selectors } => format!("find_any:{}", selectors.join("|")),
+			Operation::FindWhere { selector, label } => format!("find_where:{}:{}", selector, label),
+			Operation::FindAllWhere { selector, label, .. } => format!("find_all_where:{}:{}", selector, label),
+			Operation::FindHaving { outer, inner, .. } => format!("find_having:{}:{}", outer, inner),
+			Operation::XPath { expr } => format!("find_xpath:{}", expr),
+			Operation::XPathAll { expr, .. } => format!("find_all_xpath:{}", expr),
+			Operation::Child { index } => format!("child:{}", index),
+			Operation::ChildElement { .. } => "children_elements".to_owned(),
+			Operation::ChildText { index } => format!("text_child:{}", index),
+			Operation::PrevText => "prev_text".to_owned(),
+			Operation::NextText => "next_text".to_owned(),
+			Operation::Parent => "parent".to_owned(),
+			Operation::Ancestor { depth } => format!("ancestor:{}", depth),
+			Operation::Closest { selector } => format!("closest:{}", selector),
+			Operation::ClosestWithin { selector, max_depth } => format!("closest_within:{}:{}", selector, max_depth),
+			Operation::ClosestForm => "closest_form".to_owned(),
+			Operation::Form => "as_form".to_owned(),
+			Operation::Microdata => "microdata".to_owned(),
+			Operation::FormField { name } => format!("form_field:{}", name),
+			Operation::ClosestLink => "closest_link".to_owned(),
+			Operation::PrevHeading => "prev_heading".to_owned(),
+			Operation::Text => "text".to_owned(),
+			Operation::TextMultiline => "text_multiline".to_owned(),
+			Operation::TextCollapsed => "text_collapsed".to_owned(),
+			Operation::TextBlocks => "text_blocks".to_owned(),
+			Operation::TextUntil { selector } => format!("text_until:{}", selector),
+			Operation::SiblingBefore { .. } => "siblings_before".to_owned(),
+			Operation::SiblingAfter { .. } => "siblings_after".to_owned(),
+			Operation::Sibling { .. } => "siblings".to_owned(),
+			Operation::NextSibling => "next_sibling".to_owned(),
+			Operation::PrevSibling => "prev_sibling".to_owned(),
+			Operation::NthOfType { tag, index } => format!("nth_of_type:{}:{}", tag, index),
+			Operation::Attr { key } => format!("attr:{}", key),
+			Operation::AttrParse { key, ty } => format!("attr_parse:{}:{}", key, ty),
+			Operation::RequireClass { class } => format!("require_class:{}", class),
+			Operation::AbsHref => "abs_href".to_owned(),
+			Operation::Href => "href".to_owned(),
+			Operation::ImageSrc { index } => format!("image_src:{}", index),
+			Operation::LinkHref { index } => format!("link_href:{}", index),
+			Operation::Url => "url".to_owned(),
+			Operation::CellHeader => "cell_header".to_owned(),
+			Operation::Table => "as_table".to_owned(),
+			Operation::TableColumn { header } => format!("table_column:{}", header),
+			Operation::TableCell { row, column } => format!("table_cell:{}:{}", row, column),
+			Operation::ParseHtml => "parse_html".to_owned(),
+			Operation::Fetch { url } => format!("fetch:{}", url),
+			Operation::ParseSelector { selector } => format!("parse_selector:{}", selector),
+			Operation::CanonicalUrl => "canonical_url".to_owned(),
+			Operation::Language => "language".to_owned(),
+			Operation::Meta { name } => format!("meta:{}", name),
+			Operation::OpenGraph { property } => format!("og:{}", property),
+			Operation::JsonLd { index } => format!("json_ld:{}", index),
+			Operation::Json => "json".to_owned(),
+			Operation::ScriptById { id } => format!("script_by_id:{}", id),
+			Operation::Parse { ty } => format!("parse:{}", ty),
+			Operation::TextParse { ty } => format!("text_parse:{}", ty),
+			Operation::ParseDatetime { .. } => "parse_datetime".to_owned(),
+			Operation::Datetime => "datetime".to_owned(),
+			Operation::Regex { pattern } => format!("regex:{}", pattern),
+			Operation::EnsureNonempty => "ensure_nonempty".to_owned(),
+			Operation::MapKey => "map_key".to_owned(),
+			Operation::Owned { operation, .. } => operation.name(),
+			Operation::External => "external".to_owned(),
+		}
+	}
+}
+
+#[cfg(feature = "fetch")]
+fn fetch_error(url: &str, inner: reqwest::Error) -> Error {
+	Error {
+		reason: Reason::External(Arc::new(inner)),
+		operations: vec![Operation::Fetch { url: url.to_owned() }],
+		snapshots: Vec::new(),
+		backtrace: if backtrace_by_default() { Some(Backtrace::new()) } else { None },
+	}
+}
+
+fn links(anchors: Collection) -> impl Iterator<Item = (Node, Text)> {
+	let document = anchors.document;
+	anchors.enumerate().map(move |(index, anchor)| {
+		let href = anchor.element.value().attr("href").unwrap().trim().to_owned();
+		(anchor, Text { document, source: None, detached: Vec::new(), operation: Operation::LinkHref { index }, value: href })
+	})
+}
+
+#[cfg(feature = "chrono")]
+fn parse_naive_datetime(value: &str, format: &str) -> chrono::ParseResult<chrono::NaiveDateTime> {
+	chrono::NaiveDateTime::parse_from_str(value, format)
+		.or_else(|error| chrono::NaiveDate::parse_from_str(value, format).map(|date| date.and_time(chrono::NaiveTime::MIN)).map_err(|_| error))
+}
+
+fn parse_reason<T>(input: &str, inner: <T as FromStr>::Err) -> Reason
+where
+	T: FromStr+'static,
+	<T as FromStr>::Err: fmt::Debug+fmt::Display+Send+Sync+'static,
+{
+	let source = match (&inner as &dyn Any).downcast_ref::<ParseIntError>() {
+		Some(source) => source.clone(),
+		None => return external(inner),
+	};
+	let direction = match source.kind() {
+		IntErrorKind::PosOverflow => "large",
+		IntErrorKind::NegOverflow => "small",
+		_ => return external(inner),
+	};
+	let message = format!("value {:?} too {} for {}", input, direction, type_name::<T>());
+	Reason::External(Arc::new(Overflow { message, source }))
+}
 
 fn fmt_multiple(n: usize) -> String {
 	match n {
@@ -338,6 +2232,222 @@ fn fmt_multiple(n: usize) -> String {
 	}
 }
 
+fn collapse_whitespace(text: &str) -> String {
+	let mut collapsed = String::with_capacity(text.len());
+	let mut after_space = false;
+	for c in text.chars() {
+		if !c.is_whitespace() {
+			collapsed.push(c);
+		} else if !after_space {
+			collapsed.push(' ');
+		}
+		after_space = c.is_whitespace();
+	}
+	collapsed
+}
+
+const BLOCK_ELEMENTS: &[&str] = &[
+	"address", "article", "aside", "blockquote", "br", "dd", "div", "dl", "dt", "fieldset", "figcaption", "figure", "footer", "form", "h1", "h2",
+	"h3", "h4", "h5", "h6", "header", "hr", "li", "main", "nav", "ol", "p", "pre", "section", "table", "td", "th", "tr", "ul",
+];
+
+fn write_visible_text(element: ElementRef, text: &mut String) {
+	let name = element.value().name();
+	if ["head", "script", "style", "noscript", "template"].contains(&name) {
+		return;
+	}
+	let is_block = BLOCK_ELEMENTS.contains(&name);
+	if is_block {
+		*text += " ";
+	}
+	for child in element.children() {
+		match child.value() {
+			scraper::node::Node::Text(chunk) => *text += chunk,
+			scraper::node::Node::Element(_) => write_visible_text(ElementRef::wrap(child).unwrap(), text),
+			_ => (),
+		}
+	}
+	if is_block {
+		*text += " ";
+	}
+}
+
+fn write_markdown(element: ElementRef, markdown: &mut String, depth: usize) {
+	for child in element.children() {
+		if let Some(text) = child.value().as_text() {
+			let text = collapse_whitespace(text);
+			let after_space = markdown.is_empty() || markdown.ends_with(char::is_whitespace);
+			*markdown += if after_space { text.trim_start() } else { &text };
+		} else if let Some(child) = ElementRef::wrap(child) {
+			match child.value().name() {
+				"b" | "strong" => {
+					*markdown += "**";
+					write_markdown(child, markdown, depth);
+					*markdown += "**";
+				},
+				"i" | "em" => {
+					*markdown += "*";
+					write_markdown(child, markdown, depth);
+					*markdown += "*";
+				},
+				"a" => match child.value().attr("href") {
+					Some(href) => {
+						*markdown += "[";
+						write_markdown(child, markdown, depth);
+						*markdown += &format!("]({})", href);
+					},
+					None => write_markdown(child, markdown, depth),
+				},
+				"br" => *markdown += "\n",
+				"p" => {
+					*markdown += "\n\n";
+					write_markdown(child, markdown, depth);
+					*markdown += "\n\n";
+				},
+				name @ "ul" | name @ "ol" => {
+					for (i, item) in child.children().filter_map(ElementRef::wrap).filter(|item| item.value().name() == "li").enumerate() {
+						*markdown += "\n";
+						*markdown += &"  ".repeat(depth);
+						*markdown += &if name == "ol" { format!("{}. ", i + 1) } else { "- ".to_owned() };
+						write_markdown(item, markdown, depth + 1);
+					}
+					*markdown += "\n";
+				},
+				_ => write_markdown(child, markdown, depth),
+			}
+		}
+	}
+}
+
+fn write_text_blocks(element: ElementRef, text: &mut String, depth: usize) {
+	for child in element.children() {
+		if let Some(chunk) = child.value().as_text() {
+			let chunk = collapse_whitespace(chunk);
+			let after_space = text.is_empty() || text.ends_with(char::is_whitespace);
+			*text += if after_space { chunk.trim_start() } else { &chunk };
+		} else if let Some(child) = ElementRef::wrap(child) {
+			match child.value().name() {
+				"head" | "script" | "style" | "noscript" | "template" => (),
+				"br" => *text += "\n",
+				"td" | "th" => {
+					if !text.ends_with('\n') {
+						*text += "\t";
+					}
+					write_text_blocks(child, text, depth);
+				},
+				name @ "ul" | name @ "ol" => {
+					for (i, item) in child.children().filter_map(ElementRef::wrap).filter(|item| item.value().name() == "li").enumerate() {
+						*text += "\n";
+						*text += &"  ".repeat(depth);
+						*text += &if name == "ol" { format!("{}. ", i + 1) } else { "• ".to_owned() };
+						write_text_blocks(item, text, depth + 1);
+					}
+					*text += "\n";
+				},
+				"p" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+					*text += "\n\n";
+					write_text_blocks(child, text, depth);
+					*text += "\n\n";
+				},
+				name if BLOCK_ELEMENTS.contains(&name) => {
+					*text += "\n";
+					write_text_blocks(child, text, depth);
+					*text += "\n";
+				},
+				_ => write_text_blocks(child, text, depth),
+			}
+		}
+	}
+}
+
+/// Trims the ends of lines and the whole text, and keeps at most one blank line in a row.
+fn squash_blank_lines(text: &str) -> String {
+	let mut lines: Vec<&str> = Vec::new();
+	for line in text.lines().map(str::trim_end) {
+		if !line.is_empty() || lines.last().is_some_and(|last| !last.is_empty()) {
+			lines.push(line);
+		}
+	}
+	lines.join("\n").trim().to_owned()
+}
+
+const VOID_ELEMENTS: &[&str] = &["area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr"];
+
+fn write_normalized_html(element: ElementRef, html: &mut String) {
+	let name = element.value().name();
+	let mut attrs: Vec<_> = element.value().attrs().collect();
+	attrs.sort();
+	*html += "<";
+	*html += name;
+	for (key, value) in attrs {
+		*html += &format!(" {}=\"{}\"", key, value.replace('&', "&amp;").replace('"', "&quot;"));
+	}
+	*html += ">";
+	for child in element.children() {
+		match child.value() {
+			scraper::node::Node::Text(text) if !text.trim().is_empty() => {
+				*html += &collapse_whitespace(text).replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+			},
+			scraper::node::Node::Comment(comment) => *html += &format!("<!--{}-->", &**comment),
+			scraper::node::Node::Element(_) => write_normalized_html(ElementRef::wrap(child).unwrap(), html),
+			_ => (),
+		}
+	}
+	if !VOID_ELEMENTS.contains(&name) {
+		*html += &format!("</{}>", name);
+	}
+}
+
+fn write_redacted_html(element: ElementRef, redact: &[&str], html: &mut String) {
+	let name = element.value().name();
+	*html += "<";
+	*html += name;
+	for (key, value) in element.value().attrs() {
+		let redacted = redact.iter().any(|pattern| match pattern.strip_suffix('*') {
+			Some(prefix) => key.starts_with(prefix),
+			None => key == *pattern,
+		});
+		let value = if redacted { "***".to_owned() } else { value.replace('&', "&amp;").replace('"', "&quot;") };
+		*html += &format!(" {}=\"{}\"", key, value);
+	}
+	*html += ">";
+	for child in element.children() {
+		match child.value() {
+			scraper::node::Node::Text(text) if ["script", "style"].contains(&name) => *html += text,
+			scraper::node::Node::Text(text) => *html += &text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;"),
+			scraper::node::Node::Comment(comment) => *html += &format!("<!--{}-->", &**comment),
+			scraper::node::Node::Element(_) => write_redacted_html(ElementRef::wrap(child).unwrap(), redact, html),
+			_ => (),
+		}
+	}
+	if !VOID_ELEMENTS.contains(&name) {
+		*html += &format!("</{}>", name);
+	}
+}
+
+/// Copies the error without its backtrace, which can't be cloned. External reasons are shared between the copies.
+impl Clone for Error {
+	fn clone(&self) -> Error {
+		self.clone_shallow()
+	}
+}
+
+impl Reason {
+	pub fn kind(&self) -> ReasonKind {
+		match self {
+			Reason::NotFound => ReasonKind::NotFound,
+			Reason::MultipleFound => ReasonKind::MultipleFound,
+			Reason::ExpectedElement => ReasonKind::ExpectedElement,
+			Reason::ExpectedText => ReasonKind::ExpectedText,
+			Reason::InvalidHtml(_) => ReasonKind::InvalidHtml,
+			Reason::InvalidSelector(_) => ReasonKind::InvalidSelector,
+			Reason::MissingClass(_) => ReasonKind::MissingClass,
+			Reason::Logic(_) => ReasonKind::Logic,
+			Reason::External(_) => ReasonKind::External,
+		}
+	}
+}
+
 impl fmt::Display for Reason {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		match self {
@@ -345,6 +2455,10 @@ impl fmt::Display for Reason {
 			Reason::MultipleFound => write!(f, "found too many"),
 			Reason::ExpectedElement => write!(f, "expected element"),
 			Reason::ExpectedText => write!(f, "expected text"),
+			Reason::InvalidHtml(errors) => write!(f, "invalid html ({})", errors.join("; ")),
+			Reason::InvalidSelector(error) => write!(f, "invalid selector ({})", error),
+			Reason::MissingClass(class) => write!(f, "missing class '{}'", class),
+			Reason::Logic(message) => write!(f, "{}", message),
 			Reason::External(inner) => fmt::Display::fmt(&**inner, f),
 		}
 	}
@@ -357,21 +2471,202 @@ impl fmt::Display for Operation {
 			Operation::FindAll { selector, index } => write!(f, "{} of '{}'", fmt_multiple(*index), selector),
 			Operation::FindFirst { selector } => write!(f, "first '{}'", selector),
 			Operation::FindNth { selector, index } => write!(f, "{} '{}'", fmt_multiple(*index), selector),
+			Operation::FindAny { selectors } => write!(f, "any of '{}'", selectors.join("', '")),
+			Operation::FindWhere { selector, label } => write!(f, "'{}' where {}", selector, label),
+			Operation::FindAllWhere { selector, label, index } => write!(f, "{} of '{}' where {}", fmt_multiple(*index), selector, label),
+			Operation::FindHaving { outer, inner, index } => write!(f, "{} of '{}' having '{}'", fmt_multiple(*index), outer, inner),
+			Operation::XPath { expr } => write!(f, "xpath '{}'", expr),
+			Operation::XPathAll { expr, index } => write!(f, "{} of xpath '{}'", fmt_multiple(*index), expr),
 			Operation::Child { index } => write!(f, "{} child", fmt_multiple(*index)),
+			Operation::ChildElement { index } => write!(f, "{} child element", fmt_multiple(*index)),
 			Operation::ChildText { index } => write!(f, "{} child text", fmt_multiple(*index)),
+			Operation::PrevText => write!(f, "previous text"),
+			Operation::NextText => write!(f, "next text"),
 			Operation::Parent => write!(f, "parent"),
+			Operation::Ancestor { depth } => write!(f, "{} ancestor", fmt_multiple(*depth)),
+			Operation::Closest { selector } => write!(f, "closest '{}'", selector),
+			Operation::ClosestWithin { selector, max_depth } => write!(f, "closest '{}' within {}", selector, max_depth),
+			Operation::ClosestForm => write!(f, "closest form"),
+			Operation::Form => write!(f, "form"),
+			Operation::Microdata => write!(f, "microdata"),
+			Operation::FormField { name } => write!(f, "form field '{}'", name),
+			Operation::ClosestLink => write!(f, "closest link"),
+			Operation::PrevHeading => write!(f, "previous heading"),
 			Operation::Text => write!(f, "text"),
 			Operation::TextMultiline => write!(f, "multiline text"),
+			Operation::TextCollapsed => write!(f, "collapsed text"),
+			Operation::TextBlocks => write!(f, "block text"),
+			Operation::TextUntil { selector } => write!(f, "text until '{}'", selector),
+			Operation::SiblingBefore { index } => write!(f, "{} sibling before", fmt_multiple(*index)),
+			Operation::SiblingAfter { index } => write!(f, "{} sibling after", fmt_multiple(*index)),
+			Operation::Sibling { index } => write!(f, "{} sibling", fmt_multiple(*index)),
+			Operation::NextSibling => write!(f, "next sibling"),
+			Operation::PrevSibling => write!(f, "previous sibling"),
+			Operation::NthOfType { tag, index } => write!(f, "{} of type '{}'", fmt_multiple(*index), tag),
 			Operation::Attr { key } => write!(f, "attr '{}'", key),
-			Operation::Parse => write!(f, "parse"),
+			Operation::AttrParse { key, ty } => write!(f, "attr '{}' as {}", key, ty),
+			Operation::RequireClass { class } => write!(f, "class '{}'", class),
+			Operation::AbsHref => write!(f, "absolute href"),
+			Operation::Href => write!(f, "href"),
+			Operation::ImageSrc { index } => write!(f, "{} image source", fmt_multiple(*index)),
+			Operation::LinkHref { index } => write!(f, "{} link target", fmt_multiple(*index)),
+			Operation::Url => write!(f, "URL"),
+			Operation::CellHeader => write!(f, "cell header"),
+			Operation::Table => write!(f, "table"),
+			Operation::TableColumn { header } => write!(f, "column '{}'", header),
+			Operation::TableCell { row, column } => write!(f, "cell in row {} column {}", row + 1, column + 1),
+			Operation::ParseHtml => write!(f, "parse html"),
+			Operation::Fetch { url } => write!(f, "fetch '{}'", url),
+			Operation::ParseSelector { selector } => write!(f, "parse selector '{}'", selector),
+			Operation::CanonicalUrl => write!(f, "canonical url"),
+			Operation::Language => write!(f, "language"),
+			Operation::Meta { name } => write!(f, "meta '{}'", name),
+			Operation::OpenGraph { property } => write!(f, "Open Graph 'og:{}'", property),
+			Operation::JsonLd { index } => write!(f, "{} JSON-LD script", fmt_multiple(*index)),
+			Operation::Json => write!(f, "JSON"),
+			Operation::ScriptById { id } => write!(f, "script '#{}'", id),
+			Operation::Parse { ty } => write!(f, "parse as {}", ty),
+			Operation::TextParse { ty } => write!(f, "text as {}", ty),
+			Operation::ParseDatetime { formats } => write!(f, "parse as datetime '{}'", formats.join("', '")),
+			Operation::Datetime => write!(f, "datetime"),
+			Operation::Regex { pattern } => write!(f, "regex '{}'", pattern),
+			Operation::EnsureNonempty => write!(f, "non-empty check"),
+			Operation::MapKey => write!(f, "map key"),
+			Operation::Owned { operation, .. } => write!(f, "{}", operation),
 			Operation::External => write!(f, "external"),
 		}
 	}
 }
+/// The alternate form `{:#}` prints the same message. Saving snapshots to files is left to [`Error::save_snapshots`] and
+/// [`Error::display_with_saved_snapshots`] rather than done while formatting, since `Display` must work without a filesystem.
 impl fmt::Display for Error {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		write!(f, "{} {}", self.reason, self.operations.iter().rev().map(Operation::to_string).collect::<Vec<_>>().join(" "))
 	}
 }
+/// The source is the error inside [`Reason::External`]. Values that aren't a `std::error::Error` themselves, like the `String`
+/// errors of some `FromStr` impls, are wrapped in a type that only forwards `Debug` and `Display`; use [`Error::downcast_ref`] to
+/// get at them.
 impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error+'static)> {
+		match &self.reason {
+			Reason::External(inner) => Some(&**inner),
+			_ => None,
+		}
+	}
+}
+
+impl Error {
+	/// Stable identifiers of the operations that led to this error, outermost first, e.g. `["find_all:.question-summary",
+	/// "find:.votes span", "text", "parse:i64"]`. Each is the method name, followed by its selector, key, type or position after a colon where
+	/// it has one. Positions within `find_all` and sibling collections are left out so that failures on different rows group together.
+	pub fn operation_names(&self) -> Vec<String> {
+		self.operations.iter().map(Operation::name).collect()
+	}
+
+	/// Copy of this error for caching failed results. The backtrace is left out, since it can't be cloned and capturing a new one
+	/// would be slow and point to the wrong place; everything else, including the external reason, is kept.
+	pub fn clone_shallow(&self) -> Error {
+		Error { reason: self.reason.clone(), operations: self.operations.clone(), snapshots: self.snapshots.clone(), backtrace: None }
+	}
+
+	pub fn reason_kind(&self) -> ReasonKind {
+		self.reason.kind()
+	}
+
+	/// The error inside [`Reason::External`] or one of its sources if it's an `E`, e.g. a `ParseIntError` from [`Text::parse`],
+	/// even when it overflowed and the message was replaced with one naming the value.
+	pub fn downcast_ref<E: std::error::Error+'static>(&self) -> Option<&E> {
+		let mut inner: &(dyn std::error::Error+'static) = match &self.reason {
+			Reason::External(inner) => &**inner,
+			_ => return None,
+		};
+		loop {
+			if let Some(found) = inner.downcast_ref::<E>().or_else(|| inner.downcast_ref::<Message<E>>().map(|message| &message.0)) {
+				return Some(found);
+			}
+			inner = inner.source()?;
+		}
+	}
+
+	/// Summary of this error as plain data, for structured logging.
+	pub fn to_record(&self) -> ErrorRecord {
+		ErrorRecord::new(self)
+	}
+
+	/// [`Error::to_record`] serialized as a single line of JSON.
+	#[cfg(feature = "json")]
+	pub fn to_json(&self) -> String {
+		serde_json::to_string(&self.to_record()).unwrap()
+	}
+
+	/// Writes each snapshot to `0.html`, `1.html` and so on in `dir`, outermost first, creating the directory if needed. Returns the
+	/// paths of the written files.
+	pub fn save_snapshots(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+		std::fs::create_dir_all(dir)?;
+		let mut paths = Vec::new();
+		for (i, snapshot) in self.snapshots.iter().enumerate() {
+			let path = dir.join(format!("{}.html", i));
+			std::fs::write(&path, snapshot)?;
+			paths.push(path);
+		}
+		Ok(paths)
+	}
+
+	/// The message followed by the paths of the snapshots, saved with [`Error::save_snapshots`] to a new directory in the system's
+	/// temporary directory, for inspecting failures in production logs. Unlike `Display`, this needs a filesystem, so not on wasm.
+	pub fn display_with_saved_snapshots(&self) -> String {
+		static COUNTER: AtomicUsize = AtomicUsize::new(0);
+		let dir = std::env::temp_dir().join(format!("debris-{}-{}", std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed)));
+		match self.save_snapshots(&dir) {
+			Ok(paths) => paths.iter().fold(self.to_string(), |message, path| format!("{}\n  {}", message, path.display())),
+			Err(error) => format!("{}\n  failed to save snapshots to {} ({})", self, dir.display(), error),
+		}
+	}
+
+	/// HTML of the deepest element the failing operation ran on, e.g. the row of a `find_all` loop rather than the whole page. This is
+	/// the most useful single snapshot to log. Only `None` for errors that have no snapshots at all.
+	pub fn innermost_snapshot(&self) -> Option<&str> {
+		self.snapshots.last().map(String::as_str)
+	}
+
+	#[cfg(feature = "test-helpers")]
+	fn report(&self) -> String {
+		match self.innermost_snapshot() {
+			Some(snapshot) => format!("{}\n\n{}", self, snapshot),
+			None => self.to_string(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	thread_local!(static SERIALIZATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) });
+
+	/// [`serialize_html`] counting its calls on the current thread, so tests can check how often snapshots are serialized.
+	pub(super) fn counting_serialize(element: ElementRef, redact: &[&str]) -> String {
+		SERIALIZATIONS.with(|count| count.set(count.get() + 1));
+		serialize_html(element, redact)
+	}
+
+	fn serializations() -> usize {
+		SERIALIZATIONS.with(std::cell::Cell::get)
+	}
+
+	#[test]
+	fn snapshots_serialize_each_node_once_per_error() {
+		for redacted in [&[][..], &["data-token"][..]] {
+			let document = DocumentBuilder::new().redact_attrs(redacted).parse("<div data-token=abc><p>x</p></div>").unwrap();
+			let div = document.find("div").unwrap();
+			let before = serializations();
+			let error = div.find(".missing").unwrap_err();
+			assert_eq!(serializations() - before, 2);
+			let formatted = [error.to_string(), format!("{:?}", error), format!("{:#?}", error)];
+			assert!(formatted.iter().all(|text| !text.is_empty()));
+			assert_eq!(error.snapshots[1].contains("abc"), redacted.is_empty());
+			assert_eq!(serializations() - before, 2);
+		}
+	}
 }
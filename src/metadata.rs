@@ -0,0 +1,55 @@
+use crate::Document;
+use scraper::ElementRef;
+
+/// Common page metadata, collected with [`Document::metadata`]. Values are trimmed, and empty ones are treated as missing.
+#[derive(Clone, Debug, Default)]
+pub struct Metadata {
+	/// Text of the first `<title>`.
+	pub title: Option<String>,
+	/// The first `<meta name="description">`.
+	pub description: Option<String>,
+	/// Target of the first `<link rel="canonical">`.
+	pub canonical_url: Option<String>,
+	/// `og:*` properties without the prefix, like `("image", "https://...")`, in document order. Properties like `og:image` can
+	/// repeat.
+	pub open_graph: Vec<(String, String)>,
+	/// `twitter:*` tags without the prefix, in document order.
+	pub twitter: Vec<(String, String)>,
+}
+
+pub(crate) fn collect(document: &Document) -> Metadata {
+	let mut metadata = Metadata::default();
+	for element in document.tree.root_element().descendants().filter_map(ElementRef::wrap) {
+		let value = element.value();
+		match value.name() {
+			"title" if metadata.title.is_none() => metadata.title = nonempty(document.trim_text(&element.text().collect::<String>())),
+			"link"
+				if metadata.canonical_url.is_none()
+					&& value.attr("rel").unwrap_or("").split_whitespace().any(|rel| rel.eq_ignore_ascii_case("canonical")) =>
+			{
+				metadata.canonical_url = value.attr("href").and_then(|href| nonempty(href.trim().to_owned()))
+			},
+			"meta" => {
+				let content = match value.attr("content").and_then(|content| nonempty(content.trim().to_owned())) {
+					Some(content) => content,
+					None => continue,
+				};
+				// Open Graph specifies `property`, but `name` is common in the wild, and Twitter Cards are the other way around.
+				let key = value.attr("property").or_else(|| value.attr("name")).unwrap_or("").trim().to_ascii_lowercase();
+				if key == "description" && metadata.description.is_none() {
+					metadata.description = Some(content);
+				} else if let Some(property) = key.strip_prefix("og:") {
+					metadata.open_graph.push((property.to_owned(), content));
+				} else if let Some(property) = key.strip_prefix("twitter:") {
+					metadata.twitter.push((property.to_owned(), content));
+				}
+			},
+			_ => (),
+		}
+	}
+	metadata
+}
+
+fn nonempty(text: String) -> Option<String> {
+	if text.is_empty() { None } else { Some(text) }
+}
@@ -0,0 +1,132 @@
+use crate::{Context, Document, Node, Operation, Reason, Result};
+use ego_tree::NodeId;
+use scraper::ElementRef;
+
+/// An item of HTML microdata, the `itemscope` elements pages use to embed schema.org products, recipes, events and so on. Created
+/// with [`Document::microdata`] or [`Node::microdata`]. With the `json` feature it can be serialized with serde, with properties
+/// as a list of name and value pairs.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct Item {
+	/// URLs from `itemtype`, like `"https://schema.org/Product"`.
+	pub types: Vec<String>,
+	/// The `itemid` attribute.
+	pub id: Option<String>,
+	/// Properties in document order, followed by the ones found through `itemref`. Names can repeat, and an element with several
+	/// names in `itemprop` adds a property for each of them.
+	pub properties: Vec<(String, ItemValue)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize), serde(untagged))]
+pub enum ItemValue {
+	/// Text content, or for elements like `<a>`, `<img>`, `<meta>` and `<time>`, the attribute the microdata spec takes the value
+	/// from. URLs are returned as written, without resolving them.
+	Text(String),
+	/// An element with both `itemprop` and `itemscope`.
+	Item(Item),
+}
+
+impl Item {
+	/// First value of the property called `name`.
+	pub fn get(&self, name: &str) -> Option<&ItemValue> {
+		self.properties.iter().find(|(candidate, _)| candidate == name).map(|(_, value)| value)
+	}
+
+	pub fn get_all<'b>(&'b self, name: &'b str) -> impl Iterator<Item = &'b ItemValue> {
+		self.properties.iter().filter(move |(candidate, _)| candidate == name).map(|(_, value)| value)
+	}
+}
+
+impl ItemValue {
+	pub fn as_text(&self) -> Option<&str> {
+		match self {
+			ItemValue::Text(text) => Some(text),
+			ItemValue::Item(_) => None,
+		}
+	}
+
+	pub fn as_item(&self) -> Option<&Item> {
+		match self {
+			ItemValue::Text(_) => None,
+			ItemValue::Item(item) => Some(item),
+		}
+	}
+}
+
+pub(crate) fn top_level(document: &Document) -> Vec<Item> {
+	document
+		.tree
+		.root_element()
+		.descendants()
+		.filter_map(ElementRef::wrap)
+		.filter(|element| element.value().attr("itemscope").is_some() && element.value().attr("itemprop").is_none())
+		.map(|element| build(document, element, &mut Vec::new()))
+		.collect()
+}
+
+pub(crate) fn from_node(node: &Node) -> Result<Item> {
+	if node.element.value().attr("itemscope").is_none() {
+		return Err(node.make_error(Reason::Logic("expected an itemscope element"), Operation::Microdata));
+	}
+	Ok(build(node.document, node.element, &mut Vec::new()))
+}
+
+/// Collects the properties of the item on `element`. `items` holds the items being built further up, so that `itemref` cycles
+/// leave out the repeated item instead of recursing forever.
+fn build(document: &Document, element: ElementRef, items: &mut Vec<NodeId>) -> Item {
+	items.push(element.id());
+	let mut pending: Vec<ElementRef> = element.children().filter_map(ElementRef::wrap).collect();
+	pending.reverse();
+	for id in element.value().attr("itemref").unwrap_or("").split_whitespace() {
+		let referenced = document.tree.root_element().descendants().filter_map(ElementRef::wrap).find(|element| element.value().id() == Some(id));
+		pending.splice(..0, referenced);
+	}
+	let mut visited = vec![element.id()];
+	let mut properties = Vec::new();
+	while let Some(property) = pending.pop() {
+		if visited.contains(&property.id()) {
+			continue;
+		}
+		visited.push(property.id());
+		let nested = property.value().attr("itemscope").is_some();
+		if !nested {
+			pending.extend(property.children().filter_map(ElementRef::wrap).collect::<Vec<_>>().into_iter().rev());
+		}
+		let names = match property.value().attr("itemprop") {
+			Some(names) => names,
+			None => continue,
+		};
+		let value = if !nested {
+			ItemValue::Text(property_value(document, property))
+		} else if !items.contains(&property.id()) {
+			ItemValue::Item(build(document, property, items))
+		} else {
+			continue;
+		};
+		properties.extend(names.split_whitespace().map(|name| (name.to_owned(), value.clone())));
+	}
+	items.pop();
+	Item {
+		types: element.value().attr("itemtype").unwrap_or("").split_whitespace().map(str::to_owned).collect(),
+		id: element.value().attr("itemid").map(str::to_owned),
+		properties,
+	}
+}
+
+fn property_value(document: &Document, element: ElementRef) -> String {
+	let value = element.value();
+	let key = match value.name() {
+		"meta" => Some("content"),
+		"audio" | "embed" | "iframe" | "img" | "source" | "track" | "video" => Some("src"),
+		"a" | "area" | "link" => Some("href"),
+		"object" => Some("data"),
+		"data" | "meter" => Some("value"),
+		"time" if value.attr("datetime").is_some() => Some("datetime"),
+		_ => None,
+	};
+	match key {
+		Some(key) => value.attr(key).unwrap_or("").trim().to_owned(),
+		None => document.trim_text(&element.text().collect::<String>()),
+	}
+}
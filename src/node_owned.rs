@@ -0,0 +1,123 @@
+use crate::{Context, Document, Node, Operation, Result, SnapshotPolicy, TrimPolicy};
+use html5ever::{
+	tree_builder::{ElementFlags, NodeOrText, TreeSink},
+	Attribute, QualName,
+};
+use std::{
+	collections::HashMap,
+	sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Copy of a node that doesn't borrow its document, created with [`Node::to_owned_node`]. It can be returned from functions, stored
+/// and sent to other threads. It keeps the node's subtree, the operations that led to it and their snapshots, so errors from
+/// [`NodeOwned::with`] still show the full path from the original document.
+#[derive(Clone, Debug)]
+pub struct NodeOwned {
+	html: String,
+	nodes: Vec<OwnedNode>,
+	operations: Vec<Operation>,
+	snapshots: Vec<String>,
+	trim_policy: TrimPolicy,
+	snapshot_policy: SnapshotPolicy,
+	redacted_attrs: Vec<String>,
+	capture_backtrace: bool,
+}
+
+/// Node of the copied subtree, in document order, with the position of its parent in the same list. Values are stored as `String`s
+/// because `scraper` keeps them in tendrils, which can't be sent to other threads.
+#[derive(Clone, Debug)]
+struct OwnedNode {
+	parent: Option<usize>,
+	value: OwnedValue,
+}
+
+#[derive(Clone, Debug)]
+enum OwnedValue {
+	Element { name: QualName, attrs: Vec<(QualName, String)> },
+	Text(String),
+	Comment(String),
+}
+
+impl NodeOwned {
+	pub(crate) fn new(node: &Node) -> NodeOwned {
+		let document = node.document;
+		let mut positions = HashMap::new();
+		let mut nodes = Vec::new();
+		for descendant in node.element.descendants() {
+			let value = match descendant.value() {
+				scraper::Node::Element(element) => OwnedValue::Element {
+					name: element.name.clone(),
+					attrs: element.attrs.iter().map(|(name, value)| (name.clone(), (**value).to_owned())).collect(),
+				},
+				scraper::Node::Text(text) => OwnedValue::Text((**text).to_owned()),
+				scraper::Node::Comment(comment) => OwnedValue::Comment((**comment).to_owned()),
+				_ => continue,
+			};
+			let parent = descendant.parent().and_then(|parent| positions.get(&parent.id()).copied());
+			positions.insert(descendant.id(), nodes.len());
+			nodes.push(OwnedNode { parent, value });
+		}
+		NodeOwned {
+			html: node.outer_html(),
+			nodes,
+			operations: node.collect_operations(),
+			snapshots: node.collect_snapshots().into_iter().map(|snapshot| document.limit_snapshot(snapshot)).collect(),
+			trim_policy: document.trim_policy,
+			snapshot_policy: document.snapshot_policy.clone(),
+			redacted_attrs: document.redacted_attrs.clone(),
+			capture_backtrace: document.capture_backtrace,
+		}
+	}
+
+	pub fn html(&self) -> &str {
+		&self.html
+	}
+
+	/// Rebuilds the subtree as a document of its own and runs `f` on the node, which is the root element of that document. Errors
+	/// from it are rerooted onto the original breadcrumb and snapshots, the reverse of [`Node::extract`]. The copy is made node by
+	/// node rather than by parsing the HTML again, so elements that are only valid in context, like a lone `<td>`, are kept.
+	pub fn with<T>(&self, f: impl FnOnce(&Node) -> Result<T>) -> Result<T> {
+		// Marks the operations of this call's node, so that only errors raised from it are rerooted, even when `f` returns errors
+		// from other owned nodes with the same breadcrumb.
+		static MARKERS: AtomicUsize = AtomicUsize::new(0);
+		let marker = MARKERS.fetch_add(1, Ordering::Relaxed);
+		let mut document = Document::from_html(self.to_tree());
+		document.trim_policy = self.trim_policy;
+		document.snapshot_policy = self.snapshot_policy.clone();
+		document.redacted_attrs = self.redacted_attrs.clone();
+		document.capture_backtrace = self.capture_backtrace;
+		let operation = Operation::Owned { operation: Box::new(self.operations.last().cloned().unwrap_or(Operation::External)), marker };
+		let node = Node { document: &document, source: None, operation, element: document.tree.root_element() };
+		f(&node).map_err(|mut error| {
+			if matches!(error.operations.first(), Some(Operation::Owned { marker: first, .. }) if *first == marker) {
+				error.operations.splice(..1, self.operations.iter().cloned());
+				error.snapshots.splice(..2, self.snapshots.iter().cloned());
+			}
+			error
+		})
+	}
+
+	fn to_tree(&self) -> scraper::Html {
+		let mut tree = scraper::Html::new_fragment();
+		let root = tree.tree.root().id();
+		let mut ids = Vec::with_capacity(self.nodes.len());
+		for node in &self.nodes {
+			let parent = node.parent.map_or(root, |parent| ids[parent]);
+			let child = match &node.value {
+				OwnedValue::Element { name, attrs } => {
+					let attrs = attrs.iter().map(|(name, value)| Attribute { name: name.clone(), value: value.as_str().into() }).collect();
+					NodeOrText::AppendNode(tree.create_element(name.clone(), attrs, ElementFlags::default()))
+				},
+				OwnedValue::Text(text) => NodeOrText::AppendText(text.as_str().into()),
+				OwnedValue::Comment(comment) => NodeOrText::AppendNode(tree.create_comment(comment.as_str().into())),
+			};
+			// Text nodes never have children, so their parent's id is only a placeholder.
+			ids.push(match &child {
+				NodeOrText::AppendNode(id) => *id,
+				NodeOrText::AppendText(_) => parent,
+			});
+			tree.append(&parent, child);
+		}
+		tree
+	}
+}
@@ -0,0 +1,61 @@
+/// How [`Text::parse_number_with`](crate::Text::parse_number_with) finds a number in scraped text. The first number in the text
+/// is used and everything around it is ignored, so "1,234 pts", "$ 49.99" and "Score: -3" all work. The default reads `.` as the
+/// decimal separator, and commas, apostrophes, underscores and all kinds of spaces as grouping separators.
+#[derive(Clone, Debug)]
+pub struct NumberFormat {
+	decimal_separator: char,
+	grouping_separators: Vec<char>,
+}
+
+impl Default for NumberFormat {
+	fn default() -> NumberFormat {
+		NumberFormat { decimal_separator: '.', grouping_separators: vec![',', '\'', '_', ' ', '\u{a0}', '\u{202f}', '\u{2009}'] }
+	}
+}
+
+impl NumberFormat {
+	pub fn new() -> NumberFormat {
+		NumberFormat::default()
+	}
+
+	/// Also removes it from the grouping separators, so `decimal_separator(',')` is enough for numbers like "49,90".
+	pub fn decimal_separator(mut self, separator: char) -> NumberFormat {
+		self.decimal_separator = separator;
+		self.grouping_separators.retain(|c| *c != separator);
+		self
+	}
+
+	/// Replaces the default grouping separators, e.g. with `&['.', ' ']` for numbers like "1.234.567".
+	pub fn grouping_separators(mut self, separators: &[char]) -> NumberFormat {
+		self.grouping_separators = separators.iter().copied().filter(|c| *c != self.decimal_separator).collect();
+		self
+	}
+
+	/// The first number in `text` in the form Rust's `FromStr` expects, or `None` if it has no digits. A sign directly before the
+	/// digits is kept. Separators only count when followed by a digit, so "12. Place" reads as 12.
+	pub(crate) fn extract(&self, text: &str) -> Option<String> {
+		let chars: Vec<char> = text.chars().collect();
+		let digit_at = |i: usize| chars.get(i).is_some_and(char::is_ascii_digit);
+		let first = (0..chars.len()).find(|i| digit_at(*i))?;
+		let mut start = first;
+		if start > 0 && chars[start - 1] == self.decimal_separator {
+			start -= 1;
+		}
+		let mut number = String::new();
+		if start > 0 && matches!(chars[start - 1], '-' | '+' | '\u{2212}') {
+			number.push(if chars[start - 1] == '+' { '+' } else { '-' });
+		}
+		let mut seen_decimal = false;
+		for (i, &c) in chars.iter().enumerate().skip(start) {
+			if c.is_ascii_digit() {
+				number.push(c);
+			} else if c == self.decimal_separator && !seen_decimal && digit_at(i + 1) {
+				number.push('.');
+				seen_decimal = true;
+			} else if !self.grouping_separators.contains(&c) || seen_decimal || !digit_at(i + 1) {
+				break;
+			}
+		}
+		Some(number)
+	}
+}
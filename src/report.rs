@@ -0,0 +1,58 @@
+use crate::{truncate_chars, Error};
+#[cfg(feature = "anyhow")]
+use std::fmt;
+
+/// How much of the innermost snapshot is included, like the `{:#?}` format of [`Error`].
+const SNAPSHOT_LIMIT: usize = 2000;
+
+#[cfg(feature = "anyhow")]
+struct WithSnapshot(Error);
+
+#[cfg(feature = "anyhow")]
+impl fmt::Debug for WithSnapshot {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(&self.0, f)
+	}
+}
+
+#[cfg(feature = "anyhow")]
+impl fmt::Display for WithSnapshot {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.0)?;
+		if let Some(snapshot) = self.0.innermost_snapshot() {
+			let ellipsis = if snapshot.len() > truncate_chars(snapshot, SNAPSHOT_LIMIT).len() { "..." } else { "" };
+			write!(f, "\n\nsnapshot:\n{}{}", truncate_chars(snapshot, SNAPSHOT_LIMIT), ellipsis)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(feature = "anyhow")]
+impl std::error::Error for WithSnapshot {
+	fn source(&self) -> Option<&(dyn std::error::Error+'static)> {
+		self.0.source()
+	}
+}
+
+impl Error {
+	/// Converts into an `anyhow::Error` whose message is followed by the innermost snapshot, which plain `?` would leave out. The
+	/// result can't be downcast back into a `debris::Error`; convert with `?` instead if that's needed.
+	#[cfg(feature = "anyhow")]
+	pub fn into_anyhow(self) -> anyhow::Error {
+		anyhow::Error::new(WithSnapshot(self))
+	}
+
+	/// Converts into a `color-eyre` report, with the operations and the innermost snapshot as sections shown below the message.
+	/// The report can still be downcast into a `debris::Error`.
+	#[cfg(feature = "eyre")]
+	pub fn into_report(self) -> color_eyre::eyre::Report {
+		use color_eyre::{Section, SectionExt};
+		let operations: Vec<String> = self.operations.iter().map(ToString::to_string).collect();
+		let snapshot = self.innermost_snapshot().map(|snapshot| truncate_chars(snapshot, SNAPSHOT_LIMIT).to_owned());
+		let report = color_eyre::eyre::Report::new(self).section(operations.join("\n").header("Operations:"));
+		match snapshot {
+			Some(snapshot) => report.section(snapshot.header("Snapshot:")),
+			None => report,
+		}
+	}
+}
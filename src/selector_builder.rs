@@ -0,0 +1,70 @@
+use std::{borrow::Cow, fmt::Write};
+
+/// Assembles a compound selector from runtime values, escaping them so that user input can't change the selector's structure or
+/// make it fail to parse.
+#[derive(Clone, Debug, Default)]
+pub struct SelectorBuilder {
+	tag: String,
+	filters: String,
+}
+
+impl SelectorBuilder {
+	pub fn new() -> SelectorBuilder {
+		SelectorBuilder::default()
+	}
+
+	pub fn tag(mut self, name: &str) -> SelectorBuilder {
+		self.tag = escape_ident(name);
+		self
+	}
+
+	pub fn class(mut self, name: &str) -> SelectorBuilder {
+		self.filters += ".";
+		self.filters += &escape_ident(name);
+		self
+	}
+
+	pub fn attr_eq(mut self, name: &str, value: &str) -> SelectorBuilder {
+		write!(self.filters, "[{}={}]", escape_ident(name), escape_string(value)).unwrap();
+		self
+	}
+
+	pub fn build(&self) -> String {
+		if self.tag.is_empty() && self.filters.is_empty() { "*".to_owned() } else { format!("{}{}", self.tag, self.filters) }
+	}
+}
+
+impl From<SelectorBuilder> for Cow<'static, str> {
+	fn from(builder: SelectorBuilder) -> Self {
+		Cow::Owned(builder.build())
+	}
+}
+
+fn escape_ident(ident: &str) -> String {
+	if ident == "-" {
+		return "\\-".to_owned();
+	}
+	let mut escaped = String::new();
+	for (i, c) in ident.chars().enumerate() {
+		match c {
+			'a'..='z' | 'A'..='Z' | '_' | '-' => escaped.push(c),
+			'0'..='9' if i > 1 || (i == 1 && !ident.starts_with('-')) => escaped.push(c),
+			c if !c.is_ascii() => escaped.push(c),
+			c => write!(escaped, "\\{:x} ", c as u32).unwrap(),
+		}
+	}
+	escaped
+}
+
+fn escape_string(value: &str) -> String {
+	let mut escaped = String::from("\"");
+	for c in value.chars() {
+		match c {
+			'"' | '\\' => write!(escaped, "\\{}", c).unwrap(),
+			c if c.is_control() => write!(escaped, "\\{:x} ", c as u32).unwrap(),
+			c => escaped.push(c),
+		}
+	}
+	escaped.push('"');
+	escaped
+}
@@ -0,0 +1,119 @@
+use crate::{Context, Node, Operation, Reason, Result, Text};
+use scraper::ElementRef;
+
+/// A `<table>` laid out as a grid, created with [`Node::as_table`]. Cells spanning several rows or columns with `rowspan` and
+/// `colspan` are repeated in every position they cover, so row and column indices match what is seen in a browser.
+///
+/// Header rows are the rows in `<thead>`, or if there is none, the leading rows made only of `<th>` cells. All other rows are body
+/// rows, which are what row indices refer to. Rows of tables nested inside cells are not included.
+pub struct Table<'a> {
+	node: &'a Node<'a>,
+	headers: Vec<String>,
+	rows: Vec<Vec<Option<ElementRef<'a>>>>,
+}
+
+impl<'a> Table<'a> {
+	pub(crate) fn new(node: &'a Node<'a>) -> Result<Table<'a>> {
+		if node.element.value().name() != "table" {
+			return Err(node.make_error(Reason::Logic("expected a <table>"), Operation::Table));
+		}
+		let rows: Vec<ElementRef> = node
+			.element
+			.descendants()
+			.filter_map(ElementRef::wrap)
+			.filter(|row| {
+				row.value().name() == "tr"
+					&& row.ancestors().filter_map(ElementRef::wrap).find(|element| element.value().name() == "table") == Some(node.element)
+			})
+			.collect();
+		let in_thead = |row: &ElementRef| row.parent().and_then(ElementRef::wrap).is_some_and(|parent| parent.value().name() == "thead");
+		let header_count = if rows.iter().any(in_thead) {
+			rows.iter().take_while(|row| in_thead(row)).count()
+		} else {
+			rows.iter().take_while(|row| cells(**row).all(|cell| cell.value().name() == "th") && cells(**row).next().is_some()).count()
+		};
+		let (header_rows, body_rows) = rows.split_at(header_count);
+		let headers = match layout(header_rows).last() {
+			Some(row) => {
+				row.iter().map(|cell| cell.map_or_else(String::new, |cell| node.document.trim_text(&cell.text().collect::<String>()))).collect()
+			},
+			None => Vec::new(),
+		};
+		Ok(Table { node, headers, rows: layout(body_rows) })
+	}
+
+	/// Text of the last header row, one entry per column.
+	pub fn headers(&self) -> &[String] {
+		&self.headers
+	}
+
+	/// Number of body rows.
+	pub fn row_count(&self) -> usize {
+		self.rows.len()
+	}
+
+	/// Number of columns in the widest row, including the header.
+	pub fn column_count(&self) -> usize {
+		self.rows.iter().map(Vec::len).chain(std::iter::once(self.headers.len())).max().unwrap()
+	}
+
+	/// Index of the first column whose header is `header`.
+	pub fn column(&self, header: &str) -> Result<usize> {
+		self.headers
+			.iter()
+			.position(|candidate| candidate == header)
+			.ok_or_else(|| self.node.make_error(Reason::NotFound, Operation::TableColumn { header: header.to_owned() }))
+	}
+
+	/// The `<td>` or `<th>` covering the given position. Fails if the row is too short to reach the column.
+	pub fn cell_node(&self, row: usize, column: usize) -> Result<Node<'a>> {
+		let operation = Operation::TableCell { row, column };
+		match self.rows.get(row).and_then(|cells| cells.get(column)).copied().flatten() {
+			Some(element) => Ok(Node { document: self.node.document, source: Some(self.node), operation, element }),
+			None => Err(self.node.make_error(Reason::NotFound, operation)),
+		}
+	}
+
+	pub fn cell(&self, row: usize, column: usize) -> Result<Text<'a>> {
+		let cell = self.cell_node(row, column)?;
+		let value = self.node.document.trim_text(&cell.element.text().collect::<String>());
+		Ok(Text { document: self.node.document, source: Some(self.node), detached: Vec::new(), operation: cell.operation, value })
+	}
+
+	pub fn cell_by_header(&self, row: usize, header: &str) -> Result<Text<'a>> {
+		self.cell(row, self.column(header)?)
+	}
+}
+
+fn cells(row: ElementRef) -> impl Iterator<Item = ElementRef> {
+	row.children().filter_map(ElementRef::wrap).filter(|cell| matches!(cell.value().name(), "td" | "th"))
+}
+
+/// Places the cells of `rows` on a grid, repeating each cell across its spans. `rowspan="0"` spans the remaining rows.
+fn layout<'a>(rows: &[ElementRef<'a>]) -> Vec<Vec<Option<ElementRef<'a>>>> {
+	let mut grid: Vec<Vec<Option<ElementRef>>> = vec![Vec::new(); rows.len()];
+	for (r, row) in rows.iter().enumerate() {
+		let mut c = 0;
+		for cell in cells(*row) {
+			while grid[r].get(c).is_some_and(Option::is_some) {
+				c += 1;
+			}
+			let span = |key: &str| cell.value().attr(key).and_then(|span| span.trim().parse::<usize>().ok());
+			let rowspan = match span("rowspan") {
+				Some(0) => rows.len() - r,
+				rowspan => rowspan.unwrap_or(1).clamp(1, rows.len() - r),
+			};
+			let colspan = span("colspan").unwrap_or(1).clamp(1, 1000);
+			for grid_row in &mut grid[r..r + rowspan] {
+				if grid_row.len() < c + colspan {
+					grid_row.resize(c + colspan, None);
+				}
+				for slot in &mut grid_row[c..c + colspan] {
+					*slot = Some(cell);
+				}
+			}
+			c += colspan;
+		}
+	}
+	grid
+}
@@ -0,0 +1,60 @@
+/// How [`Text::normalized_with`](crate::Text::normalized_with) cleans up whitespace. By default every whitespace run, including
+/// line breaks and non-breaking spaces, becomes a single space, and the ends are trimmed.
+#[derive(Clone, Copy, Debug)]
+pub struct TextOptions {
+	trim: bool,
+	keep_newlines: bool,
+	keep_nbsp: bool,
+}
+
+impl Default for TextOptions {
+	fn default() -> TextOptions {
+		TextOptions { trim: true, keep_newlines: false, keep_nbsp: false }
+	}
+}
+
+impl TextOptions {
+	pub fn new() -> TextOptions {
+		TextOptions::default()
+	}
+
+	/// Whether whitespace at the ends is removed rather than collapsed to a single character.
+	pub fn trim(mut self, trim: bool) -> TextOptions {
+		self.trim = trim;
+		self
+	}
+
+	/// Whether runs containing a line break collapse to a single `\n` instead of a space, for text split into lines.
+	pub fn keep_newlines(mut self, keep_newlines: bool) -> TextOptions {
+		self.keep_newlines = keep_newlines;
+		self
+	}
+
+	/// Whether non-breaking spaces are left as they are, for text where they glue a number to its unit on purpose.
+	pub fn keep_nbsp(mut self, keep_nbsp: bool) -> TextOptions {
+		self.keep_nbsp = keep_nbsp;
+		self
+	}
+
+	pub(crate) fn apply(&self, text: &str) -> String {
+		let mut normalized = String::with_capacity(text.len());
+		let mut pending = None;
+		for c in text.chars() {
+			if c.is_whitespace() && !(self.keep_nbsp && matches!(c, '\u{a0}' | '\u{2007}' | '\u{202f}')) {
+				let newline = pending == Some('\n') || (self.keep_newlines && c == '\n');
+				pending = Some(if newline { '\n' } else { ' ' });
+				continue;
+			}
+			if let Some(separator) = pending.take() {
+				if !(self.trim && normalized.is_empty()) {
+					normalized.push(separator);
+				}
+			}
+			normalized.push(c);
+		}
+		if let Some(separator) = pending.filter(|_| !self.trim) {
+			normalized.push(separator);
+		}
+		normalized
+	}
+}
@@ -0,0 +1,316 @@
+use crate::collapse_whitespace;
+use ego_tree::iter::Edge;
+use scraper::{ElementRef, Selector};
+
+/// A selector compiled by `scraper`, or one using the jQuery-style text pseudo-classes, which `scraper` doesn't support:
+///
+/// - `:contains("...")` matches elements whose text, including all descendants, contains the string.
+/// - `:text-is("...")` matches elements whose text is exactly the string, after trimming and collapsing whitespace.
+///
+/// The argument can be quoted with `"` or `'`, with `\` escaping the next character. Selectors using them are split into their
+/// compound selectors, which are compiled by `scraper` one by one and chained with the combinators here.
+pub(crate) enum CompiledSelector {
+	Css(Selector),
+	Text(Vec<Vec<Step>>),
+}
+
+pub(crate) struct Step {
+	/// How this compound relates to the one matched before it, `None` for the first one.
+	combinator: Option<Combinator>,
+	css: Selector,
+	filters: Vec<TextFilter>,
+}
+
+#[derive(Clone, Copy)]
+enum Combinator {
+	Descendant,
+	Child,
+	NextSibling,
+	SubsequentSibling,
+}
+
+enum TextFilter {
+	Contains(String),
+	Is(String),
+}
+
+#[derive(Clone)]
+pub(crate) enum Select<'a> {
+	Css(scraper::element_ref::Select<'a, 'a>),
+	Text(ego_tree::iter::Traverse<'a, scraper::Node>, &'a CompiledSelector),
+}
+
+impl CompiledSelector {
+	pub(crate) fn parse(selector: &str) -> Result<CompiledSelector, String> {
+		if !selector.contains(":contains(") && !selector.contains(":text-is(") {
+			return Selector::parse(selector).map(CompiledSelector::Css).map_err(|error| format!("{:?}", error));
+		}
+		split(selector, ',')?.into_iter().map(parse_complex).collect::<Result<_, _>>().map(CompiledSelector::Text)
+	}
+
+	/// The `scraper` selector, if no text pseudo-classes were used.
+	pub(crate) fn as_css(&self) -> Option<&Selector> {
+		match self {
+			CompiledSelector::Css(css) => Some(css),
+			CompiledSelector::Text(_) => None,
+		}
+	}
+
+	pub(crate) fn matches(&self, element: &ElementRef) -> bool {
+		match self {
+			CompiledSelector::Css(css) => css.matches(element),
+			CompiledSelector::Text(complexes) => complexes.iter().any(|steps| matches_steps(steps, *element)),
+		}
+	}
+
+	/// Descendants of `root` that match, in document order, not including `root` itself.
+	pub(crate) fn select<'a>(&'a self, root: ElementRef<'a>) -> Select<'a> {
+		match self {
+			CompiledSelector::Css(css) => Select::Css(root.select(css)),
+			CompiledSelector::Text(_) => {
+				let mut traverse = root.traverse();
+				traverse.next();
+				Select::Text(traverse, self)
+			},
+		}
+	}
+}
+
+impl<'a> Iterator for Select<'a> {
+	type Item = ElementRef<'a>;
+
+	fn next(&mut self) -> Option<ElementRef<'a>> {
+		match self {
+			Select::Css(select) => select.next(),
+			Select::Text(traverse, selector) => traverse
+				.filter_map(|edge| match edge {
+					Edge::Open(node) => ElementRef::wrap(node),
+					Edge::Close(_) => None,
+				})
+				.find(|element| selector.matches(element)),
+		}
+	}
+}
+
+/// Whether `element` matches the last step, and the steps before it can be matched walking from there.
+fn matches_steps(steps: &[Step], element: ElementRef) -> bool {
+	let (last, rest) = match steps.split_last() {
+		Some(split) => split,
+		None => return true,
+	};
+	if !last.css.matches(&element) || !last.filters.iter().all(|filter| filter.matches(element)) {
+		return false;
+	}
+	let mut candidates: Box<dyn Iterator<Item = ElementRef>> = match last.combinator {
+		None => return true,
+		Some(Combinator::Descendant) => Box::new(element.ancestors().filter_map(ElementRef::wrap)),
+		Some(Combinator::Child) => Box::new(element.parent().and_then(ElementRef::wrap).into_iter()),
+		Some(Combinator::NextSibling) => Box::new(element.prev_siblings().filter_map(ElementRef::wrap).take(1)),
+		Some(Combinator::SubsequentSibling) => Box::new(element.prev_siblings().filter_map(ElementRef::wrap)),
+	};
+	candidates.any(|candidate| matches_steps(rest, candidate))
+}
+
+impl TextFilter {
+	fn matches(&self, element: ElementRef) -> bool {
+		let text: String = element.text().collect();
+		match self {
+			TextFilter::Contains(needle) => text.contains(needle.as_str()),
+			TextFilter::Is(expected) => collapse_whitespace(&text).trim() == expected,
+		}
+	}
+}
+
+fn parse_complex(selector: &str) -> Result<Vec<Step>, String> {
+	let mut steps = Vec::new();
+	let mut combinator = None;
+	let mut compound = String::new();
+	let mut filters = Vec::new();
+	let mut chars = selector.trim().chars().peekable();
+	while let Some(c) = chars.next() {
+		let next = match c {
+			'>' => Some(Combinator::Child),
+			'+' => Some(Combinator::NextSibling),
+			'~' => Some(Combinator::SubsequentSibling),
+			c if c.is_whitespace() => Some(Combinator::Descendant),
+			_ => None,
+		};
+		if let Some(next) = next {
+			if !compound.is_empty() || !filters.is_empty() {
+				steps.push(step(combinator.take(), &compound, std::mem::take(&mut filters))?);
+				compound.clear();
+				combinator = Some(Combinator::Descendant);
+			}
+			if !matches!(next, Combinator::Descendant) {
+				if steps.is_empty() {
+					return Err(format!("selector starts with a combinator: {:?}", selector));
+				}
+				combinator = Some(next);
+			}
+			continue;
+		}
+		match c {
+			':' if matches_ahead(&chars, "contains(") || matches_ahead(&chars, "text-is(") => {
+				let name: String = chars.by_ref().take_while(|c| *c != '(').collect();
+				let argument = parse_argument(&mut chars)?;
+				filters.push(if name == "contains" { TextFilter::Contains(argument) } else { TextFilter::Is(argument) });
+			},
+			'(' | '[' | '"' | '\'' => {
+				compound.push(c);
+				copy_group(c, &mut chars, &mut compound)?;
+			},
+			'\\' => {
+				compound.push(c);
+				compound.extend(chars.next());
+			},
+			_ => compound.push(c),
+		}
+	}
+	if compound.is_empty() && filters.is_empty() {
+		return Err(format!("selector is empty or ends with a combinator: {:?}", selector));
+	}
+	steps.push(step(combinator, &compound, filters)?);
+	Ok(steps)
+}
+
+fn step(combinator: Option<Combinator>, compound: &str, filters: Vec<TextFilter>) -> Result<Step, String> {
+	let css = if compound.is_empty() { "*" } else { compound };
+	let css = Selector::parse(css).map_err(|error| format!("{:?}", error))?;
+	Ok(Step { combinator, css, filters })
+}
+
+fn matches_ahead(chars: &std::iter::Peekable<std::str::Chars>, expected: &str) -> bool {
+	chars.clone().take(expected.len()).eq(expected.chars())
+}
+
+/// Reads the argument of a text pseudo-class up to its closing parenthesis.
+fn parse_argument(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<String, String> {
+	while chars.next_if(|c| c.is_whitespace()).is_some() {}
+	let mut argument = String::new();
+	match chars.peek().copied() {
+		Some(quote @ ('"' | '\'')) => {
+			chars.next();
+			loop {
+				match chars.next() {
+					Some('\\') => argument.extend(chars.next()),
+					Some(c) if c == quote => break,
+					Some(c) => argument.push(c),
+					None => return Err("unterminated string in text pseudo-class".to_owned()),
+				}
+			}
+			while chars.next_if(|c| c.is_whitespace()).is_some() {}
+			match chars.next() {
+				Some(')') => Ok(argument),
+				_ => Err("expected ')' after text pseudo-class argument".to_owned()),
+			}
+		},
+		_ => {
+			for c in chars {
+				if c == ')' {
+					return Ok(argument.trim().to_owned());
+				}
+				argument.push(c);
+			}
+			Err("unterminated text pseudo-class".to_owned())
+		},
+	}
+}
+
+/// Copies a bracketed or quoted group that was opened with `open` as it is, so combinators and commas inside it are left alone.
+fn copy_group(open: char, chars: &mut impl Iterator<Item = char>, out: &mut String) -> Result<(), String> {
+	let close = match open {
+		'(' => ')',
+		'[' => ']',
+		quote => quote,
+	};
+	while let Some(c) = chars.next() {
+		out.push(c);
+		match c {
+			'\\' => out.extend(chars.next()),
+			c if c == close => return Ok(()),
+			'(' | '[' | '"' | '\'' if open != '"' && open != '\'' => copy_group(c, chars, out)?,
+			_ => (),
+		}
+	}
+	Err(format!("unclosed {:?}", open))
+}
+
+/// Splits on `separator` outside of brackets and quotes.
+fn split(selector: &str, separator: char) -> Result<Vec<&str>, String> {
+	let mut parts = Vec::new();
+	let mut start = 0;
+	let mut chars = selector.char_indices();
+	while let Some((i, c)) = chars.next() {
+		match c {
+			'(' | '[' | '"' | '\'' => copy_group(c, &mut chars.by_ref().map(|(_, c)| c), &mut String::new())?,
+			c if c == separator => {
+				parts.push(&selector[start..i]);
+				start = i + c.len_utf8();
+			},
+			_ => (),
+		}
+	}
+	parts.push(&selector[start..]);
+	Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::CompiledSelector;
+	use scraper::Html;
+
+	/// Ids of the elements `selector` finds in `html`, in document order.
+	fn ids(html: &str, selector: &str) -> Vec<String> {
+		let html = Html::parse_fragment(html);
+		let selector = CompiledSelector::parse(selector).unwrap();
+		selector.select(html.root_element()).filter_map(|element| element.value().id()).map(str::to_owned).collect()
+	}
+
+	fn error(selector: &str) -> String {
+		CompiledSelector::parse(selector).err().unwrap()
+	}
+
+	const FRUITS: &str = "<div id=d><p id=p1>apple</p><span id=s1>x</span><p id=p2>pear</p><section id=s2><p id=p3>apple pie</p></section></div>";
+
+	#[test]
+	fn combinators_around_contains() {
+		assert_eq!(ids(FRUITS, "div > p:contains(apple)"), ["p1"]);
+		assert_eq!(ids(FRUITS, "div p:contains(apple)"), ["p1", "p3"]);
+		assert_eq!(ids(FRUITS, "p:contains(apple) + span"), ["s1"]);
+		assert_eq!(ids(FRUITS, "p:contains(apple)~p"), ["p2"]);
+		assert_eq!(ids(FRUITS, "div > section p:contains(pie)"), ["p3"]);
+		assert_eq!(ids(FRUITS, "div>:contains(apple)"), ["p1", "s2"]);
+		assert_eq!(ids(FRUITS, ":contains(pear)"), ["d", "p2"]);
+	}
+
+	#[test]
+	fn quoted_arguments_with_escapes() {
+		let html = r#"<p id=a>a "(b)" c)</p><p id=b>it's</p><p id=c>x</p>"#;
+		assert_eq!(ids(html, r#"p:contains("a \"(b)\" c)")"#), ["a"]);
+		assert_eq!(ids(html, r"p:contains('it\'s')"), ["b"]);
+		assert_eq!(ids(html, "p:text-is( 'x' )"), ["c"]);
+	}
+
+	#[test]
+	fn comma_lists() {
+		let html = "<ul><li id=a>a</li><li id=b>b, c</li><li id=c>c</li></ul>";
+		assert_eq!(ids(html, "li:text-is(a), li:contains('b, c')"), ["a", "b"]);
+		assert_eq!(ids(html, "li:text-is(c),li#a"), ["a", "c"]);
+	}
+
+	#[test]
+	fn contains_inside_attribute_values() {
+		let html = "<a id=a title=':contains(x)'>1</a><a id=b title=other>2</a>";
+		assert!(CompiledSelector::parse("a[title=':contains(x)']").unwrap().as_css().is_none());
+		assert_eq!(ids(html, "a[title=':contains(x)']"), ["a"]);
+		assert_eq!(ids(html, "a[title=':contains(x)']:contains(2)"), Vec::<String>::new());
+		assert_eq!(ids(html, "a:not([title=':contains(x)']):contains(2)"), ["b"]);
+	}
+
+	#[test]
+	fn misplaced_combinators() {
+		assert_eq!(error("> p:contains(a)"), r#"selector starts with a combinator: "> p:contains(a)""#);
+		assert_eq!(error("p:contains(a) >"), r#"selector is empty or ends with a combinator: "p:contains(a) >""#);
+		assert_eq!(error("p:contains(a),"), r#"selector is empty or ends with a combinator: """#);
+	}
+}
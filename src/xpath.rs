@@ -0,0 +1,701 @@
+use ego_tree::{NodeId, NodeRef};
+use scraper::{node::Node, ElementRef};
+use std::{cell::OnceCell, collections::{HashMap, HashSet}};
+
+/// Compiled XPath 1.0 location path. Only the subset that scrapers commonly use is supported: the abbreviated syntax (`/`, `//`,
+/// `.`, `..`, `@`), the child, descendant, descendant-or-self, parent, ancestor, ancestor-or-self, following-sibling,
+/// preceding-sibling, self and attribute axes, the `*`, `text()` and `node()` tests, and predicates made of positions, `and`, `or`,
+/// comparisons and the functions listed in [`FUNCTIONS`]. Predicates can also filter a parenthesized path as a whole, so
+/// `(//li)[1]` is the first `<li>` in the document, while `//li[1]` is the first one of each list. Arithmetic, unions and variables
+/// are not supported.
+pub struct XPath {
+	path: Path,
+}
+
+/// Supported functions with the minimum and maximum number of arguments.
+const FUNCTIONS: &[(&str, usize, usize)] = &[
+	("position", 0, 0),
+	("last", 0, 0),
+	("count", 1, 1),
+	("contains", 2, 2),
+	("starts-with", 2, 2),
+	("normalize-space", 0, 1),
+	("string", 0, 1),
+	("not", 1, 1),
+	("name", 0, 1),
+	("true", 0, 0),
+	("false", 0, 0),
+];
+
+struct Path {
+	absolute: bool,
+	filter: Option<Box<Filter>>,
+	steps: Vec<Step>,
+}
+
+/// Parenthesized path with predicates, whose positions count over the whole result in document order.
+struct Filter {
+	path: Path,
+	predicates: Vec<Expr>,
+}
+
+struct Step {
+	axis: Axis,
+	test: Test,
+	predicates: Vec<Expr>,
+}
+
+#[derive(Clone, Copy)]
+enum Axis {
+	Child,
+	Descendant,
+	DescendantOrSelf,
+	Parent,
+	Ancestor,
+	AncestorOrSelf,
+	FollowingSibling,
+	PrecedingSibling,
+	Itself,
+	Attribute,
+}
+
+enum Test {
+	Name(String),
+	Any,
+	Text,
+	Node,
+}
+
+enum Expr {
+	Path(Path),
+	Literal(String),
+	Number(f64),
+	Function(String, Vec<Expr>),
+	Compare(Box<Expr>, Comparison, Box<Expr>),
+	And(Box<Expr>, Box<Expr>),
+	Or(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparison {
+	Eq,
+	Ne,
+	Lt,
+	Le,
+	Gt,
+	Ge,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+	Slash,
+	DoubleSlash,
+	Dot,
+	DotDot,
+	At,
+	ColonColon,
+	LeftBracket,
+	RightBracket,
+	LeftParen,
+	RightParen,
+	Comma,
+	Star,
+	Compare(Comparison),
+	Name(String),
+	Literal(String),
+	Number(f64),
+}
+
+impl XPath {
+	pub fn parse(expr: &str) -> Result<XPath, String> {
+		let mut parser = Parser { tokens: tokenize(expr)?, position: 0 };
+		let expr = parser.or()?;
+		if let Some(token) = parser.peek() {
+			return Err(format!("unexpected {:?}", token));
+		}
+		match expr {
+			Expr::Path(path) => Ok(XPath { path }),
+			_ => Err("expression doesn't select nodes".to_owned()),
+		}
+	}
+
+	/// Elements selected with `context` as the context node, in document order.
+	pub fn evaluate<'a>(&self, context: NodeRef<'a, Node>) -> Vec<ElementRef<'a>> {
+		let root = std::iter::once(context).chain(context.ancestors()).last().unwrap();
+		let evaluator = Evaluator { root, order: OnceCell::new() };
+		evaluator
+			.path(&self.path, Item::Node(context))
+			.into_iter()
+			.filter_map(|item| match item {
+				Item::Node(node) => ElementRef::wrap(node),
+				Item::Attr { .. } => None,
+			})
+			.collect()
+	}
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>, String> {
+	let mut tokens = Vec::new();
+	let mut rest = expr.trim_start();
+	while let Some(c) = rest.chars().next() {
+		let (token, len) = match c {
+			'/' if rest.starts_with("//") => (Token::DoubleSlash, 2),
+			'/' => (Token::Slash, 1),
+			'.' if rest.starts_with("..") => (Token::DotDot, 2),
+			'.' if !rest[1..].starts_with(|c: char| c.is_ascii_digit()) => (Token::Dot, 1),
+			'0'..='9' | '.' => {
+				let len = rest.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(rest.len());
+				let number = rest[..len].parse().map_err(|_| format!("invalid number {:?}", &rest[..len]))?;
+				(Token::Number(number), len)
+			},
+			'@' => (Token::At, 1),
+			':' if rest.starts_with("::") => (Token::ColonColon, 2),
+			'[' => (Token::LeftBracket, 1),
+			']' => (Token::RightBracket, 1),
+			'(' => (Token::LeftParen, 1),
+			')' => (Token::RightParen, 1),
+			',' => (Token::Comma, 1),
+			'*' => (Token::Star, 1),
+			'=' => (Token::Compare(Comparison::Eq), 1),
+			'!' if rest.starts_with("!=") => (Token::Compare(Comparison::Ne), 2),
+			'<' if rest.starts_with("<=") => (Token::Compare(Comparison::Le), 2),
+			'<' => (Token::Compare(Comparison::Lt), 1),
+			'>' if rest.starts_with(">=") => (Token::Compare(Comparison::Ge), 2),
+			'>' => (Token::Compare(Comparison::Gt), 1),
+			'"' | '\'' => {
+				let len = rest[1..].find(c).ok_or("unterminated string")?;
+				(Token::Literal(rest[1..1 + len].to_owned()), len + 2)
+			},
+			c if c.is_alphabetic() || c == '_' => {
+				let len = rest.find(|c: char| !c.is_alphanumeric() && !matches!(c, '_' | '-' | '.')).unwrap_or(rest.len());
+				(Token::Name(rest[..len].to_owned()), len)
+			},
+			c => return Err(format!("unexpected '{}'", c)),
+		};
+		tokens.push(token);
+		rest = rest[len..].trim_start();
+	}
+	Ok(tokens)
+}
+
+struct Parser {
+	tokens: Vec<Token>,
+	position: usize,
+}
+
+impl Parser {
+	fn or(&mut self) -> Result<Expr, String> {
+		let mut expr = self.and()?;
+		while self.eat_name("or") {
+			expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+		}
+		Ok(expr)
+	}
+
+	fn and(&mut self) -> Result<Expr, String> {
+		let mut expr = self.comparison()?;
+		while self.eat_name("and") {
+			expr = Expr::And(Box::new(expr), Box::new(self.comparison()?));
+		}
+		Ok(expr)
+	}
+
+	fn comparison(&mut self) -> Result<Expr, String> {
+		let expr = self.primary()?;
+		match self.peek() {
+			Some(Token::Compare(comparison)) => {
+				let comparison = *comparison;
+				self.position += 1;
+				Ok(Expr::Compare(Box::new(expr), comparison, Box::new(self.primary()?)))
+			},
+			_ => Ok(expr),
+		}
+	}
+
+	fn primary(&mut self) -> Result<Expr, String> {
+		match self.peek().cloned() {
+			Some(Token::Literal(literal)) => {
+				self.position += 1;
+				Ok(Expr::Literal(literal))
+			},
+			Some(Token::Number(number)) => {
+				self.position += 1;
+				Ok(Expr::Number(number))
+			},
+			Some(Token::LeftParen) => {
+				self.position += 1;
+				let expr = self.or()?;
+				self.expect(Token::RightParen)?;
+				if !matches!(self.peek(), Some(Token::LeftBracket | Token::Slash | Token::DoubleSlash)) {
+					return Ok(expr);
+				}
+				let path = match expr {
+					Expr::Path(path) => path,
+					_ => return Err("only paths can be filtered or continued".to_owned()),
+				};
+				let predicates = self.predicates()?;
+				let mut steps = Vec::new();
+				match self.peek() {
+					Some(Token::Slash) => self.position += 1,
+					Some(Token::DoubleSlash) => {
+						self.position += 1;
+						steps.push(Step::descendant_or_self());
+					},
+					_ => return Ok(Expr::Path(Path { absolute: false, filter: Some(Box::new(Filter { path, predicates })), steps })),
+				}
+				self.steps(&mut steps)?;
+				Ok(Expr::Path(Path { absolute: false, filter: Some(Box::new(Filter { path, predicates })), steps }))
+			},
+			Some(Token::Name(name)) if self.tokens.get(self.position + 1) == Some(&Token::LeftParen) && name != "text" && name != "node" => {
+				self.position += 2;
+				let mut args = Vec::new();
+				if !self.eat(&Token::RightParen) {
+					loop {
+						args.push(self.or()?);
+						if !self.eat(&Token::Comma) {
+							break;
+						}
+					}
+					self.expect(Token::RightParen)?;
+				}
+				match FUNCTIONS.iter().find(|(function, ..)| *function == name) {
+					Some((_, min, max)) if (*min..=*max).contains(&args.len()) => Ok(Expr::Function(name, args)),
+					Some(_) => Err(format!("wrong number of arguments to {}()", name)),
+					None => Err(format!("unsupported function {}()", name)),
+				}
+			},
+			_ => self.path().map(Expr::Path),
+		}
+	}
+
+	fn path(&mut self) -> Result<Path, String> {
+		let mut steps = Vec::new();
+		let absolute = match self.peek() {
+			Some(Token::Slash) => true,
+			Some(Token::DoubleSlash) => {
+				steps.push(Step::descendant_or_self());
+				true
+			},
+			_ => false,
+		};
+		if absolute {
+			self.position += 1;
+			if !matches!(self.peek(), Some(Token::Dot | Token::DotDot | Token::At | Token::Star | Token::Name(_))) && steps.is_empty() {
+				return Ok(Path { absolute, filter: None, steps });
+			}
+		}
+		self.steps(&mut steps)?;
+		Ok(Path { absolute, filter: None, steps })
+	}
+
+	/// Steps separated by `/` or `//`, appended to `steps`.
+	fn steps(&mut self, steps: &mut Vec<Step>) -> Result<(), String> {
+		loop {
+			steps.push(self.step()?);
+			match self.peek() {
+				Some(Token::Slash) => self.position += 1,
+				Some(Token::DoubleSlash) => {
+					self.position += 1;
+					steps.push(Step::descendant_or_self());
+				},
+				_ => return Ok(()),
+			}
+		}
+	}
+
+	fn step(&mut self) -> Result<Step, String> {
+		if self.eat(&Token::Dot) {
+			return Ok(Step { axis: Axis::Itself, test: Test::Node, predicates: Vec::new() });
+		}
+		if self.eat(&Token::DotDot) {
+			return Ok(Step { axis: Axis::Parent, test: Test::Node, predicates: Vec::new() });
+		}
+		let axis = if self.eat(&Token::At) {
+			Axis::Attribute
+		} else if let (Some(Token::Name(name)), Some(Token::ColonColon)) = (self.peek(), self.tokens.get(self.position + 1)) {
+			let axis = match name.as_str() {
+				"child" => Axis::Child,
+				"descendant" => Axis::Descendant,
+				"descendant-or-self" => Axis::DescendantOrSelf,
+				"parent" => Axis::Parent,
+				"ancestor" => Axis::Ancestor,
+				"ancestor-or-self" => Axis::AncestorOrSelf,
+				"following-sibling" => Axis::FollowingSibling,
+				"preceding-sibling" => Axis::PrecedingSibling,
+				"self" => Axis::Itself,
+				"attribute" => Axis::Attribute,
+				_ => return Err(format!("unsupported axis {}", name)),
+			};
+			self.position += 2;
+			axis
+		} else {
+			Axis::Child
+		};
+		let test = match self.peek().cloned() {
+			Some(Token::Star) => Test::Any,
+			Some(Token::Name(name)) if self.tokens.get(self.position + 1) == Some(&Token::LeftParen) => {
+				self.position += 2;
+				self.expect(Token::RightParen)?;
+				match name.as_str() {
+					"text" => Test::Text,
+					"node" => Test::Node,
+					_ => return Err(format!("unsupported node test {}()", name)),
+				}
+			},
+			Some(Token::Name(name)) => Test::Name(name),
+			token => return Err(format!("expected a node test, found {}", describe(token.as_ref()))),
+		};
+		if let Test::Any | Test::Name(_) = test {
+			self.position += 1;
+		}
+		Ok(Step { axis, test, predicates: self.predicates()? })
+	}
+
+	fn predicates(&mut self) -> Result<Vec<Expr>, String> {
+		let mut predicates = Vec::new();
+		while self.eat(&Token::LeftBracket) {
+			predicates.push(self.or()?);
+			self.expect(Token::RightBracket)?;
+		}
+		Ok(predicates)
+	}
+
+	fn peek(&self) -> Option<&Token> {
+		self.tokens.get(self.position)
+	}
+
+	fn eat(&mut self, token: &Token) -> bool {
+		let matches = self.peek() == Some(token);
+		if matches {
+			self.position += 1;
+		}
+		matches
+	}
+
+	fn eat_name(&mut self, name: &str) -> bool {
+		self.eat(&Token::Name(name.to_owned()))
+	}
+
+	fn expect(&mut self, token: Token) -> Result<(), String> {
+		if self.eat(&token) { Ok(()) } else { Err(format!("expected {:?}, found {}", token, describe(self.peek()))) }
+	}
+}
+
+fn describe(token: Option<&Token>) -> String {
+	match token {
+		Some(token) => format!("{:?}", token),
+		None => "end of expression".to_owned(),
+	}
+}
+
+impl Step {
+	fn descendant_or_self() -> Step {
+		Step { axis: Axis::DescendantOrSelf, test: Test::Node, predicates: Vec::new() }
+	}
+}
+
+impl Test {
+	fn matches(&self, node: &NodeRef<Node>) -> bool {
+		match self {
+			Test::Name(name) => node.value().as_element().is_some_and(|element| element.name().eq_ignore_ascii_case(name)),
+			Test::Any => node.value().is_element(),
+			Test::Text => node.value().is_text(),
+			Test::Node => true,
+		}
+	}
+}
+
+#[derive(Clone, Copy)]
+enum Item<'a> {
+	Node(NodeRef<'a, Node>),
+	Attr { name: &'a str, value: &'a str },
+}
+
+enum Value<'a> {
+	Items(Vec<Item<'a>>),
+	String(String),
+	Number(f64),
+	Boolean(bool),
+}
+
+struct Focus<'a> {
+	item: Item<'a>,
+	position: usize,
+	size: usize,
+}
+
+struct Evaluator<'a> {
+	root: NodeRef<'a, Node>,
+	order: OnceCell<HashMap<NodeId, usize>>,
+}
+
+impl<'a> Evaluator<'a> {
+	fn path(&self, path: &Path, context: Item<'a>) -> Vec<Item<'a>> {
+		let mut items = match &path.filter {
+			Some(filter) => self.filter(self.path(&filter.path, context), &filter.predicates),
+			None => vec![if path.absolute { Item::Node(self.root) } else { context }],
+		};
+		for step in &path.steps {
+			let next = items.iter().flat_map(|item| self.step(step, *item)).collect();
+			items = if items.len() > 1 { self.sort_unique(next) } else { next };
+		}
+		items
+	}
+
+	fn step(&self, step: &Step, item: Item<'a>) -> Vec<Item<'a>> {
+		let node = match item {
+			Item::Node(node) => node,
+			Item::Attr { .. } if matches!((step.axis, &step.test), (Axis::Itself, Test::Node)) => return vec![item],
+			Item::Attr { .. } => return Vec::new(),
+		};
+		let candidates = match (step.axis, node.value().as_element()) {
+			(Axis::Attribute, Some(element)) => element
+				.attrs()
+				.filter(|(name, _)| match &step.test {
+					Test::Name(test) => name.eq_ignore_ascii_case(test),
+					Test::Any | Test::Node => true,
+					Test::Text => false,
+				})
+				.map(|(name, value)| Item::Attr { name, value })
+				.collect(),
+			(Axis::Attribute, None) => Vec::new(),
+			(axis, _) => walk(axis, node).filter(|node| step.test.matches(node)).map(Item::Node).collect(),
+		};
+		// Predicates count positions along the axis, but results are returned in document order.
+		let mut items = self.filter(candidates, &step.predicates);
+		if let Axis::Ancestor | Axis::AncestorOrSelf | Axis::PrecedingSibling = step.axis {
+			items.reverse();
+		}
+		items
+	}
+
+	fn filter(&self, mut items: Vec<Item<'a>>, predicates: &[Expr]) -> Vec<Item<'a>> {
+		for predicate in predicates {
+			let size = items.len();
+			items = items
+				.into_iter()
+				.enumerate()
+				.filter(|(i, item)| match self.eval(predicate, &Focus { item: *item, position: i + 1, size }) {
+					Value::Number(number) => number == (i + 1) as f64,
+					value => value.boolean(),
+				})
+				.map(|(_, item)| item)
+				.collect();
+		}
+		items
+	}
+
+	fn eval(&self, expr: &Expr, focus: &Focus<'a>) -> Value<'a> {
+		match expr {
+			Expr::Path(path) => Value::Items(self.path(path, focus.item)),
+			Expr::Literal(literal) => Value::String(literal.clone()),
+			Expr::Number(number) => Value::Number(*number),
+			Expr::Function(name, args) => self.function(name, args, focus),
+			Expr::Compare(left, comparison, right) => Value::Boolean(compare(&self.eval(left, focus), *comparison, &self.eval(right, focus))),
+			Expr::And(left, right) => Value::Boolean(self.eval(left, focus).boolean() && self.eval(right, focus).boolean()),
+			Expr::Or(left, right) => Value::Boolean(self.eval(left, focus).boolean() || self.eval(right, focus).boolean()),
+		}
+	}
+
+	fn function(&self, name: &str, args: &[Expr], focus: &Focus<'a>) -> Value<'a> {
+		let string = |index: usize| args.get(index).map_or_else(|| string_value(focus.item), |arg| self.eval(arg, focus).string());
+		match name {
+			"position" => Value::Number(focus.position as f64),
+			"last" => Value::Number(focus.size as f64),
+			"count" => Value::Number(match self.eval(&args[0], focus) {
+				Value::Items(items) => items.len() as f64,
+				_ => 0.,
+			}),
+			"contains" => Value::Boolean(string(0).contains(&string(1))),
+			"starts-with" => Value::Boolean(string(0).starts_with(&string(1))),
+			"normalize-space" => Value::String(string(0).split_whitespace().collect::<Vec<_>>().join(" ")),
+			"string" => Value::String(string(0)),
+			"not" => Value::Boolean(!self.eval(&args[0], focus).boolean()),
+			"name" => {
+				let item = match args.first().map(|arg| self.eval(arg, focus)) {
+					Some(Value::Items(items)) => items.first().copied(),
+					Some(_) => None,
+					None => Some(focus.item),
+				};
+				Value::String(item.map_or_else(String::new, item_name))
+			},
+			"true" => Value::Boolean(true),
+			"false" => Value::Boolean(false),
+			_ => unreachable!("function {}() should have been rejected by the parser", name),
+		}
+	}
+
+	fn sort_unique(&self, items: Vec<Item<'a>>) -> Vec<Item<'a>> {
+		let order = self.order.get_or_init(|| self.root.descendants().enumerate().map(|(i, node)| (node.id(), i)).collect());
+		let mut seen = HashSet::new();
+		let mut nodes = Vec::new();
+		let mut attrs = Vec::new();
+		for item in items {
+			match item {
+				Item::Node(node) if seen.insert(node.id()) => nodes.push(node),
+				Item::Node(_) => (),
+				Item::Attr { .. } => attrs.push(item),
+			}
+		}
+		nodes.sort_by_key(|node| order[&node.id()]);
+		nodes.into_iter().map(Item::Node).chain(attrs).collect()
+	}
+}
+
+fn walk<'a>(axis: Axis, node: NodeRef<'a, Node>) -> Box<dyn Iterator<Item = NodeRef<'a, Node>>+'a> {
+	match axis {
+		Axis::Child => Box::new(node.children()),
+		Axis::Descendant => Box::new(node.descendants().skip(1)),
+		Axis::DescendantOrSelf => Box::new(node.descendants()),
+		Axis::Parent => Box::new(node.parent().into_iter()),
+		Axis::Ancestor => Box::new(node.ancestors()),
+		Axis::AncestorOrSelf => Box::new(std::iter::once(node).chain(node.ancestors())),
+		Axis::FollowingSibling => Box::new(node.next_siblings()),
+		Axis::PrecedingSibling => Box::new(node.prev_siblings()),
+		Axis::Itself => Box::new(std::iter::once(node)),
+		Axis::Attribute => Box::new(std::iter::empty()),
+	}
+}
+
+fn string_value(item: Item) -> String {
+	match item {
+		Item::Node(node) => match node.value() {
+			Node::Text(text) => text.to_string(),
+			Node::Comment(comment) => comment.to_string(),
+			Node::Element(_) | Node::Document | Node::Fragment => {
+				node.descendants().filter_map(|node| node.value().as_text()).map(|text| &**text).collect()
+			},
+			_ => String::new(),
+		},
+		Item::Attr { value, .. } => value.to_owned(),
+	}
+}
+
+fn item_name(item: Item) -> String {
+	match item {
+		Item::Node(node) => node.value().as_element().map_or_else(String::new, |element| element.name().to_owned()),
+		Item::Attr { name, .. } => name.to_owned(),
+	}
+}
+
+impl Value<'_> {
+	fn boolean(&self) -> bool {
+		match self {
+			Value::Items(items) => !items.is_empty(),
+			Value::String(string) => !string.is_empty(),
+			Value::Number(number) => *number != 0. && !number.is_nan(),
+			Value::Boolean(boolean) => *boolean,
+		}
+	}
+
+	fn string(&self) -> String {
+		match self {
+			Value::Items(items) => items.first().map_or_else(String::new, |item| string_value(*item)),
+			Value::String(string) => string.clone(),
+			Value::Number(number) if number.fract() == 0. && number.is_finite() => format!("{}", *number as i64),
+			Value::Number(number) => number.to_string(),
+			Value::Boolean(boolean) => boolean.to_string(),
+		}
+	}
+
+	fn number(&self) -> f64 {
+		match self {
+			Value::Number(number) => *number,
+			Value::Boolean(boolean) => if *boolean { 1. } else { 0. },
+			value => value.string().trim().parse().unwrap_or(f64::NAN),
+		}
+	}
+}
+
+fn compare(left: &Value, comparison: Comparison, right: &Value) -> bool {
+	match (left, right) {
+		(Value::Items(_), Value::Boolean(_)) | (Value::Boolean(_), Value::Items(_)) => {
+			compare_scalars(&Value::Boolean(left.boolean()), comparison, &Value::Boolean(right.boolean()))
+		},
+		(Value::Items(items), _) => items.iter().any(|item| compare(&Value::String(string_value(*item)), comparison, right)),
+		(_, Value::Items(items)) => items.iter().any(|item| compare(left, comparison, &Value::String(string_value(*item)))),
+		_ => compare_scalars(left, comparison, right),
+	}
+}
+
+fn compare_scalars(left: &Value, comparison: Comparison, right: &Value) -> bool {
+	match comparison {
+		Comparison::Eq | Comparison::Ne => {
+			let equal = match (left, right) {
+				(Value::Boolean(_), _) | (_, Value::Boolean(_)) => left.boolean() == right.boolean(),
+				(Value::Number(_), _) | (_, Value::Number(_)) => left.number() == right.number(),
+				_ => left.string() == right.string(),
+			};
+			equal == (comparison == Comparison::Eq)
+		},
+		Comparison::Lt => left.number() < right.number(),
+		Comparison::Le => left.number() <= right.number(),
+		Comparison::Gt => left.number() > right.number(),
+		Comparison::Ge => left.number() >= right.number(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::XPath;
+	use scraper::Html;
+
+	/// Whitespace-normalized text of each element `expr` selects in `html`.
+	fn texts(html: &str, expr: &str) -> Vec<String> {
+		let html = Html::parse_fragment(html);
+		let elements = XPath::parse(expr).unwrap().evaluate(html.tree.root());
+		elements.iter().map(|element| element.text().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" ")).collect()
+	}
+
+	fn names(html: &str, expr: &str) -> Vec<String> {
+		let html = Html::parse_fragment(html);
+		XPath::parse(expr).unwrap().evaluate(html.tree.root()).iter().map(|element| element.value().name().to_owned()).collect()
+	}
+
+	fn error(expr: &str) -> String {
+		XPath::parse(expr).err().unwrap()
+	}
+
+	#[test]
+	fn positions_per_step_and_per_filter() {
+		let html = "<ul><li>a</li><li>b</li></ul><ul><li>c</li></ul>";
+		assert_eq!(texts(html, "//li[1]"), ["a", "c"]);
+		assert_eq!(texts(html, "(//li)[1]"), ["a"]);
+		assert_eq!(texts(html, "(//li)[last()]"), ["c"]);
+		assert_eq!(texts(html, "(//ul)[2]/li"), ["c"]);
+		assert_eq!(texts(html, "//li[position() > 1]"), ["b"]);
+	}
+
+	#[test]
+	fn reverse_axes_count_from_the_context() {
+		let html = "<div><section><p>x</p><h2>t</h2><span>s</span><b>y</b></section></div>";
+		assert_eq!(names(html, "//b/ancestor::*[1]"), ["section"]);
+		assert_eq!(names(html, "//b/ancestor::*[2]"), ["div"]);
+		assert_eq!(names(html, "//b/preceding-sibling::*[1]"), ["span"]);
+		assert_eq!(names(html, "//b/preceding-sibling::*[last()]"), ["p"]);
+		assert_eq!(names(html, "//b/preceding-sibling::*"), ["p", "h2", "span"]);
+	}
+
+	#[test]
+	fn comparisons_and_functions() {
+		let html = "<ul><li class='new sale'>  Big   sale </li><li class=old>Apple pie</li><li><a>1</a><a>2</a></li></ul>";
+		assert_eq!(texts(html, "//li[text()='Apple pie']"), ["Apple pie"]);
+		assert_eq!(texts(html, "//li[@class='old']"), ["Apple pie"]);
+		assert_eq!(texts(html, "//li[@class!='old']"), ["Big sale"]);
+		assert_eq!(texts(html, "//li[contains(@class, 'sale')]"), ["Big sale"]);
+		assert_eq!(texts(html, "//li[starts-with(., 'Apple')]"), ["Apple pie"]);
+		assert_eq!(texts(html, "//li[normalize-space()='Big sale']"), ["Big sale"]);
+		assert_eq!(texts(html, "//li[count(a) = 2]"), ["12"]);
+		assert_eq!(texts(html, "//li[last()]/a[position() = last()]"), ["2"]);
+		assert_eq!(texts(html, "//li[not(@class)]/a[1]"), ["1"]);
+	}
+
+	#[test]
+	fn unsupported_syntax_errors() {
+		assert_eq!(error("//a | //b"), "unexpected '|'");
+		assert_eq!(error("//li[last() - 1]"), "unexpected '-'");
+		assert_eq!(error("//li/following::a"), "unsupported axis following");
+		assert_eq!(error("//li[lower-case(.) = 'a']"), "unsupported function lower-case()");
+		assert_eq!(error("('a')[1]"), "only paths can be filtered or continued");
+	}
+}
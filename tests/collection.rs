@@ -0,0 +1,110 @@
+use debris::{Document, Find, ReasonKind, Result};
+use std::collections::HashMap;
+
+fn list(n: usize) -> Document {
+	Document::new(&format!("<ul>{}</ul>", (0..n).map(|i| format!("<li>{}</li>", i)).collect::<String>()))
+}
+
+#[test]
+fn chunks_with_partial_last_chunk() -> Result<()> {
+	let document = list(10);
+	let chunks: Vec<Vec<String>> = document.find_all("li")?.chunks(3).map(|chunk| chunk.iter().map(|node| node.text().string()).collect()).collect();
+	assert_eq!(chunks, [vec!["0", "1", "2"], vec!["3", "4", "5"], vec!["6", "7", "8"], vec!["9"]]);
+	Ok(())
+}
+
+#[test]
+fn into_map_from_two_column_table() -> Result<()> {
+	let document = Document::new("<table><tr><td>apple</td><td>3</td></tr><tr><td>pear</td><td>5</td></tr></table>");
+	let map: HashMap<String, u32> = document.find_all("tr")?.into_map("td:first-child", "td:last-child")?;
+	assert_eq!(map.len(), 2);
+	assert_eq!(map["apple"], 3);
+	assert_eq!(map["pear"], 5);
+	let duplicated = Document::new("<table><tr><td>apple</td><td>3</td></tr><tr><td>apple</td><td>5</td></tr></table>");
+	let err = duplicated.find_all("tr")?.into_map::<String, u32>("td:first-child", "td:last-child").unwrap_err();
+	assert_eq!(err.reason_kind(), ReasonKind::Logic);
+	Ok(())
+}
+
+#[test]
+fn peek_then_consume_same_node() -> Result<()> {
+	let document = list(2);
+	let mut items = document.find_all("li")?;
+	assert_eq!(items.peek().unwrap().text(), "0");
+	assert_eq!(items.peek().unwrap().text(), "0");
+	assert_eq!(items.next().unwrap().text(), "0");
+	assert_eq!(items.peek().unwrap().text(), "1");
+	assert_eq!(items.next().unwrap().text(), "1");
+	assert!(items.peek().is_none());
+	Ok(())
+}
+
+#[test]
+fn find_map_node_returns_first_mapped_value() -> Result<()> {
+	let document = Document::new("<ul><li>soon</li><li>12</li><li>30</li></ul>");
+	let first_number = document.find_all("li")?.find_map_node(|node| node.text().parse::<u32>().ok());
+	assert_eq!(first_number, Some(12));
+	let big = document.find_all("li")?.find_map_node(|node| node.text().parse::<u32>().ok().filter(|n| *n > 100));
+	assert_eq!(big, None);
+	Ok(())
+}
+
+#[test]
+fn with_field_pairs_rows_with_titles() -> Result<()> {
+	let document = Document::new("<ul><li><h3>First</h3></li><li><h3>Second</h3></li><li>untitled</li></ul>");
+	let mut rows = document.find_all("li")?.with_field("h3");
+	assert_eq!(rows.next().unwrap()?.1, "First");
+	let (row, title) = rows.next().unwrap()?;
+	assert_eq!(title, "Second");
+	assert_eq!(row.find("h3")?.text(), "Second");
+	assert_eq!(rows.next().unwrap().unwrap_err().reason_kind(), ReasonKind::NotFound);
+	assert!(rows.next().is_none());
+	Ok(())
+}
+
+#[test]
+fn with_field_errors_match_reading_the_field_from_the_row() -> Result<()> {
+	let document = Document::new("<ul><li><h3>12</h3></li><li><h3>many</h3><p>x</p></li></ul>");
+	let ul = document.find("ul")?;
+	let mut rows = ul.find_all("li")?.with_field("h3");
+	assert_eq!(rows.next().unwrap()?.1.parse::<u32>()?, 12);
+	let (row, title) = rows.next().unwrap()?;
+	let err = title.parse::<u32>().unwrap_err();
+	let direct = row.find("h3")?.text().parse::<u32>().unwrap_err();
+	assert_eq!(err.operation_names(), ["find:ul", "find_all:li", "find:h3", "text", "parse:u32"]);
+	assert_eq!(err.operation_names(), direct.operation_names());
+	assert_eq!(err.to_string(), direct.to_string());
+	assert_eq!(err.snapshots, direct.snapshots);
+	assert!(err.snapshots.iter().any(|snapshot| snapshot == "<li><h3>many</h3><p>x</p></li>"), "{:?}", err.snapshots);
+	assert_eq!(err.innermost_snapshot(), Some("<h3>many</h3>"));
+	assert_eq!(title.normalized().parse::<u32>().unwrap_err().snapshots, direct.snapshots);
+	Ok(())
+}
+
+#[test]
+fn zip_nodes_pairs_columns() -> Result<()> {
+	let document = Document::new("<dl><dt>Color</dt><dd>red</dd><dt>Size</dt><dd>M</dd><dt>Fit</dt></dl>");
+	let pairs: Vec<(String, String)> =
+		document.find_all("dt")?.zip_nodes(document.find_all("dd")?).map(|(dt, dd)| (dt.text().string(), dd.text().string())).collect();
+	assert_eq!(pairs, [("Color".to_owned(), "red".to_owned()), ("Size".to_owned(), "M".to_owned())]);
+	let equal = document.find_all("dd")?.zip_nodes(document.find_all("dd")?).count();
+	assert_eq!(equal, 2);
+	Ok(())
+}
+
+#[test]
+fn count_remaining_and_is_empty_do_not_consume() -> Result<()> {
+	let document = list(3);
+	let mut items = document.find_all("li")?;
+	assert_eq!(items.count_remaining(), 3);
+	assert!(!items.is_empty());
+	assert_eq!(items.next().unwrap().text().string(), "0");
+	assert_eq!(items.count_remaining(), 2);
+	let rest: Vec<String> = items.collect_nodes().iter().map(|node| node.text().string()).collect();
+	assert_eq!(rest, ["1", "2"]);
+	let missing = document.find_all("ol")?;
+	assert!(missing.is_empty());
+	assert_eq!(missing.count_remaining(), 0);
+	assert!(missing.collect_nodes().is_empty());
+	Ok(())
+}
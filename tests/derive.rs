@@ -0,0 +1,47 @@
+#![cfg(feature = "derive")]
+
+use debris::{Document, Find, FromHtml, ReasonKind, Result};
+
+#[derive(FromHtml)]
+struct Author {
+	#[debris(select = "a", text)]
+	name: String,
+	#[debris(select = "a", attr = "href")]
+	profile: String,
+}
+
+#[derive(FromHtml)]
+struct Question {
+	#[debris(select = ".title", text)]
+	title: String,
+	#[debris(select = ".votes", text, parse)]
+	votes: i64,
+	#[debris(attr = "data-id", parse)]
+	id: u32,
+	#[debris(select = ".author")]
+	author: Author,
+}
+
+const QUESTIONS: &str = concat!(
+	"<div class=q data-id=7><a class=title>How?</a><span class=votes>12</span><div class=author><a href=/u/1>ann</a></div></div>",
+	"<div class=q data-id=8><a class=title>Why?</a><span class=votes>many</span><div class=author><a href=/u/2>bob</a></div></div>",
+);
+
+#[test]
+fn derived_struct_extracts_fields_and_nested_structs() -> Result<()> {
+	let document = Document::new(QUESTIONS);
+	let question = Question::from_node(&document.find_first(".q")?)?;
+	assert_eq!((question.title.as_str(), question.votes, question.id), ("How?", 12, 7));
+	assert_eq!((question.author.name.as_str(), question.author.profile.as_str()), ("ann", "/u/1"));
+	Ok(())
+}
+
+#[test]
+fn derived_struct_errors_name_the_failing_field() -> Result<()> {
+	let document = Document::new(QUESTIONS);
+	let err = document.find_all(".q")?.map(|node| Question::from_node(&node)).collect::<Result<Vec<_>>>().err().unwrap();
+	assert_eq!(err.reason_kind(), ReasonKind::External);
+	assert_eq!(err.operation_names(), ["find_all:.q", "find:.votes", "text", "parse:i64"]);
+	assert!(err.innermost_snapshot().unwrap().contains("data-id=\"8\""));
+	Ok(())
+}
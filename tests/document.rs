@@ -0,0 +1,285 @@
+use debris::{Document, DocumentBuilder, Find, Item, ItemValue, Reason, ReasonKind, Result, SnapshotPolicy, TrimPolicy, Truncation};
+
+#[test]
+fn subtree_document_scopes_selectors() -> Result<()> {
+	let document = Document::new("<div id=a><p>one</p></div><div id=b><p>two</p></div>");
+	assert!(document.find("p").is_err());
+	let subtree = document.find("#b")?.to_document();
+	assert_eq!(subtree.find("p")?.text(), "two");
+	assert!(subtree.find("#a").is_err());
+	Ok(())
+}
+
+#[cfg(feature = "url")]
+#[test]
+fn href_resolves_against_configured_base() -> Result<()> {
+	let mut document = Document::new("<head><base href=\"https://cdn.example.com/\"></head><a href=\"../b/c.html\">c</a>");
+	assert_eq!(document.find("a")?.href()?, "https://cdn.example.com/b/c.html");
+	document.set_base("https://example.com/a/index.html").unwrap();
+	let link = document.find("a")?;
+	assert_eq!(link.href()?, "https://example.com/b/c.html");
+	assert_eq!(link.abs_href()?, link.href()?.string().as_str());
+	Ok(())
+}
+
+#[cfg(feature = "url")]
+#[test]
+fn text_url_resolves_against_base_url() -> Result<()> {
+	let mut document = Document::new("<p id=rel> ../img/a.png </p><p id=abs>https://other.org/x</p><p id=bad>http://[::1</p>");
+	let relative = document.find("#rel")?.text().url().unwrap_err();
+	assert_eq!(relative.downcast_ref::<url::ParseError>(), Some(&url::ParseError::RelativeUrlWithoutBase));
+	assert_eq!(relative.operation_names(), ["find:#rel", "text", "url"]);
+	assert_eq!(relative.to_string(), "relative URL without a base URL text '#rel'");
+	assert_eq!(document.find("#abs")?.text().url()?.as_str(), "https://other.org/x");
+	document.set_base_url(url::Url::parse("https://example.com/a/index.html").unwrap());
+	assert_eq!(document.find("#rel")?.text().url()?.as_str(), "https://example.com/img/a.png");
+	assert_eq!(document.find("#abs")?.text().url()?.as_str(), "https://other.org/x");
+	let bad = document.find("#bad")?.text().url().unwrap_err();
+	assert_eq!(bad.reason_kind(), ReasonKind::External);
+	assert!(bad.downcast_ref::<url::ParseError>().is_some());
+	Ok(())
+}
+
+#[test]
+fn find_any_falls_back_to_later_selector() -> Result<()> {
+	let document = Document::new("<div class=price-new>$5</div>");
+	assert_eq!(document.find_any(&[".price", ".price-new"])?.text(), "$5");
+	let err = document.find_any(&[".price", ".cost"]).unwrap_err();
+	assert_eq!(err.to_string(), "not found any of '.price', '.cost'");
+	Ok(())
+}
+
+#[test]
+fn all_text_skips_scripts_and_separates_blocks() {
+	let document =
+		Document::new("<head><title>t</title></head><body><p>one</p><p>two</p><script>var x = 1;</script><div>three<span>four</span></div></body>");
+	assert_eq!(document.all_text(), "one two threefour");
+}
+
+#[test]
+fn canonical_url_with_and_without_link() {
+	let document =
+		Document::new(r#"<head><link rel="stylesheet" href="/s.css"><link rel="Canonical alternate" href=" https://example.com/a "></head>"#);
+	assert_eq!(document.canonical_url().unwrap(), "https://example.com/a");
+	assert!(Document::new(r#"<head><link rel="stylesheet" href="/s.css"></head>"#).canonical_url().is_none());
+}
+
+#[test]
+fn from_html_wraps_parsed_tree() -> Result<()> {
+	let tree = scraper::Html::parse_fragment("<p class=lead>hello</p>");
+	let document = Document::from_html(tree);
+	assert_eq!(document.find(".lead")?.text(), "hello");
+	Ok(())
+}
+
+#[test]
+fn language_from_html_lang() {
+	assert_eq!(Document::new(r#"<html lang="en-US"><body></body></html>"#).language().unwrap(), "en-US");
+	assert!(Document::new("<p>no language</p>").language().is_none());
+}
+
+#[test]
+fn images_with_lazy_loaded_source() {
+	let document = Document::new(
+		r#"<img src="/a.png"><img src="data:image/gif;base64,R0lGOD" data-src="/b.png"><img data-lazy-src=" /c.png "><img alt=empty>"#,
+	);
+	let sources: Vec<String> = document.images().map(|(_, src)| src.string()).collect();
+	assert_eq!(sources, ["/a.png", "/b.png", "/c.png"]);
+}
+
+#[test]
+fn builder_applies_strict_and_collapse() -> Result<()> {
+	let builder = DocumentBuilder::new().strict(true).trim_policy(TrimPolicy::Collapse);
+	let document = builder.clone().parse("<!DOCTYPE html><p>  in \n  stock </p>")?;
+	assert_eq!(document.find("p")?.text(), "in stock");
+	let err = builder.parse("<p>no doctype</p>").unwrap_err();
+	assert_eq!(err.reason_kind(), ReasonKind::InvalidHtml);
+	Ok(())
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn builder_decodes_bytes_with_charset() -> Result<()> {
+	let bytes = b"<!DOCTYPE html><p> caf\xe9 \n au lait </p>";
+	let document = DocumentBuilder::new().strict(true).trim_policy(TrimPolicy::Collapse).parse_bytes(bytes, Some("text/html; charset=windows-1252"))?;
+	assert_eq!(document.find("p")?.text(), "café au lait");
+	Ok(())
+}
+
+#[test]
+fn json_ld_and_script_by_id() {
+	let document = Document::new(concat!(
+		r#"<script type="application/ld+json"> {"@type": "Product"} </script><script type=text/javascript>var a;</script>"#,
+		r#"<script id=state type="application/json">{"a": 1}</script>"#,
+	));
+	let blocks = document.json_ld();
+	assert_eq!(blocks.len(), 1);
+	assert_eq!(blocks[0], r#"{"@type": "Product"}"#);
+	assert_eq!(document.script_by_id("state").unwrap(), r#"{"a": 1}"#);
+	assert!(document.script_by_id("missing").is_none());
+}
+
+/// The page snapshot of an error from a document parsed from `html` with `policy`.
+fn page_snapshot(html: &str, policy: SnapshotPolicy) -> String {
+	let document = DocumentBuilder::new().snapshot_policy(policy).parse(html).unwrap();
+	document.find(".missing").unwrap_err().snapshots.remove(0)
+}
+
+#[test]
+fn snapshot_truncation_strategies() {
+	let digits = || SnapshotPolicy::new().redact(|_| "0123456789".to_owned());
+	assert_eq!(page_snapshot("<p>x</p>", digits()), "0123456789");
+	assert_eq!(page_snapshot("<p>x</p>", digits().max_length(10)), "0123456789");
+	assert_eq!(page_snapshot("<p>x</p>", digits().max_length(4)), "0123");
+	assert_eq!(page_snapshot("<p>x</p>", digits().max_length(4).truncation(Truncation::KeepEnd)), "6789");
+	let accents = SnapshotPolicy::new().redact(|_| "ąęćżź".to_owned()).max_length(2);
+	assert_eq!(page_snapshot("<p>x</p>", accents), "ąę");
+}
+
+#[test]
+fn snapshot_keep_both_marks_omitted_middle() {
+	let digits = || SnapshotPolicy::new().redact(|_| "0123456789".to_owned()).truncation(Truncation::KeepBoth);
+	assert_eq!(page_snapshot("<p>x</p>", digits().max_length(5)), "012<!-- 5 characters omitted -->89");
+	assert_eq!(page_snapshot("<p>x</p>", digits().max_length(4)), "01<!-- 6 characters omitted -->89");
+	assert_eq!(page_snapshot("<p>x</p>", digits().max_length(12)), "0123456789");
+}
+
+#[test]
+fn snapshot_redacted_before_truncation() {
+	let policy = SnapshotPolicy::new().redact(|snapshot| snapshot.replace("SECRET", "***")).max_length(21).truncation(Truncation::KeepEnd);
+	assert_eq!(page_snapshot("<p>SECRET</p>", policy), "***</p></body></html>");
+}
+
+/// Text of the `<p>` in a page decoded with [`Document::from_bytes`].
+#[cfg(feature = "encoding")]
+fn decoded_paragraph(bytes: &[u8], content_type: Option<&str>) -> String {
+	Document::from_bytes(bytes, content_type).find("p").unwrap().text().string()
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn from_bytes_byte_order_mark() {
+	assert_eq!(decoded_paragraph(b"\xef\xbb\xbf<p>caf\xc3\xa9</p>", None), "café");
+	assert_eq!(decoded_paragraph(b"\xff\xfe<\0p\0>\0c\0a\0f\0\xe9\0<\0/\0p\0>\0", None), "café");
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn from_bytes_content_type_header() {
+	assert_eq!(decoded_paragraph(b"<p>caf\xe9</p>", Some("text/html; charset=ISO-8859-1")), "café");
+	assert_eq!(decoded_paragraph(b"<p>caf\xc3\xa9</p>", Some("text/html;charset=\"utf-8\"")), "café");
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn from_bytes_meta_prescan() {
+	let http_equiv = b"<meta http-equiv=Content-Type content='text/html; charset=windows-1252'><p>caf\xe9</p>";
+	assert_eq!(decoded_paragraph(http_equiv, None), "café");
+	assert_eq!(decoded_paragraph(b"<META CHARSET=\"Windows-1252\"><p>caf\xe9</p>", None), "café");
+	assert_eq!(decoded_paragraph(b"<meta charset=utf-16><p>caf\xc3\xa9</p>", None), "café");
+	assert_eq!(decoded_paragraph(b"<p>caf\xe9</p>", None), "café");
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn from_bytes_precedence() {
+	let windows_1252 = Some("text/html; charset=windows-1252");
+	assert_eq!(decoded_paragraph(b"\xef\xbb\xbf<meta charset=windows-1252><p>caf\xc3\xa9</p>", windows_1252), "café");
+	assert_eq!(decoded_paragraph(b"<meta charset=utf-8><p>caf\xe9</p>", windows_1252), "café");
+	assert_eq!(decoded_paragraph(b"<meta charset=utf-8><p>caf\xe9</p>", None), "caf\u{fffd}");
+}
+
+fn text(value: &str) -> ItemValue {
+	ItemValue::Text(value.to_owned())
+}
+
+fn item(types: &[&str], id: Option<&str>, properties: Vec<(&str, ItemValue)>) -> Item {
+	let properties = properties.into_iter().map(|(name, value)| (name.to_owned(), value)).collect();
+	Item { types: types.iter().map(|ty| (*ty).to_owned()).collect(), id: id.map(str::to_owned), properties }
+}
+
+#[test]
+fn microdata_nested_items_and_itemref_cycles() -> Result<()> {
+	let document = Document::new(concat!(
+		"<div itemscope itemtype=https://schema.org/Product itemid=urn:lamp itemref=extra>",
+		"<span itemprop=name>Lamp</span>",
+		"<div id=offer itemprop=offers itemscope itemtype=https://schema.org/Offer itemref='offer seller'>",
+		"<meta itemprop=price content=9.99></div>",
+		"</div>",
+		"<p id=extra itemprop='brand maker'>Acme</p>",
+		"<div id=seller itemprop=seller itemscope itemref=offer><span itemprop=name>Shop</span></div>",
+		"<div id=loop itemscope itemref=loop><span itemprop=name>Loop</span></div>",
+	));
+	let seller = item(&[], None, vec![("name", text("Shop"))]);
+	let offer = item(&["https://schema.org/Offer"], None, vec![("price", text("9.99")), ("seller", ItemValue::Item(seller))]);
+	let product = item(&["https://schema.org/Product"], Some("urn:lamp"), vec![
+		("name", text("Lamp")),
+		("offers", ItemValue::Item(offer)),
+		("brand", text("Acme")),
+		("maker", text("Acme")),
+	]);
+	let looped = item(&[], None, vec![("name", text("Loop"))]);
+	assert_eq!(document.microdata(), [product.clone(), looped]);
+	assert_eq!(document.find_first("[itemscope]")?.microdata()?, product);
+	assert_eq!(product.get("offers").and_then(ItemValue::as_item).and_then(|offer| offer.get("price")), Some(&text("9.99")));
+	let err = document.find("#extra")?.microdata().unwrap_err();
+	assert_eq!(err.reason_kind(), ReasonKind::Logic);
+	Ok(())
+}
+
+#[test]
+fn meta_og_and_metadata_from_head() -> Result<()> {
+	let document = Document::new(concat!(
+		"<head><title> Lamp | Shop </title>",
+		"<meta name=Description content=' A lamp. '><meta name=description content=second>",
+		"<meta property=og:title content=Lamp><meta property=og:image content=/a.png><meta property=og:image content=/b.png>",
+		"<meta name=og:type content=product><meta property=og:empty content=' '><meta name=twitter:card content=summary>",
+		"<link rel=canonical href=' https://example.com/lamp '></head>",
+	));
+	assert_eq!(document.meta("description")?, "A lamp.");
+	assert_eq!(document.meta("author").unwrap_err().to_string(), "not found meta 'author'");
+	assert_eq!(document.og("image")?, "/a.png");
+	assert_eq!(document.og("type")?, "product");
+	assert_eq!(document.og("TITLE")?, "Lamp");
+	assert_eq!(document.og("price").unwrap_err().reason_kind(), ReasonKind::NotFound);
+	let metadata = document.metadata();
+	assert_eq!(metadata.title.as_deref(), Some("Lamp | Shop"));
+	assert_eq!(metadata.description.as_deref(), Some("A lamp."));
+	assert_eq!(metadata.canonical_url.as_deref(), Some("https://example.com/lamp"));
+	let pairs = |tags: &[(String, String)]| tags.iter().map(|(key, value)| format!("{}={}", key, value)).collect::<Vec<_>>();
+	assert_eq!(pairs(&metadata.open_graph), ["title=Lamp", "image=/a.png", "image=/b.png", "type=product"]);
+	assert_eq!(pairs(&metadata.twitter), ["card=summary"]);
+	Ok(())
+}
+
+#[test]
+fn new_strict_rejects_malformed_html() {
+	let malformed = "<!DOCTYPE html><p>unclosed <b>bold</p></div>";
+	let lenient = Document::new(malformed);
+	let problems: Vec<&str> = lenient.parse_errors().collect();
+	assert!(!problems.is_empty());
+	let err = Document::new_strict(malformed).unwrap_err();
+	assert_eq!(err.operation_names(), ["parse_html"]);
+	match &err.reason {
+		Reason::InvalidHtml(errors) => assert_eq!(errors, &problems),
+		reason => panic!("unexpected reason {:?}", reason),
+	}
+	assert!(err.to_string().starts_with("invalid html ("), "{}", err);
+	let valid = Document::new_strict("<!DOCTYPE html><p>fine</p>").unwrap();
+	assert_eq!(valid.parse_errors().count(), 0);
+}
+
+#[test]
+fn new_fragment_has_no_head_or_body() -> Result<()> {
+	let fragment = Document::new_fragment("<li>a</li><li>b</li><p>x</p>");
+	assert_eq!(fragment.html(), "<html><li>a</li><li>b</li><p>x</p></html>");
+	let items: Vec<String> = fragment.find_all(":root > li")?.map(|li| li.text().string()).collect();
+	assert_eq!(items, ["a", "b"]);
+	assert_eq!(fragment.find(":root > p")?.text(), "x");
+	assert_eq!(fragment.find("body").unwrap_err().reason_kind(), ReasonKind::NotFound);
+	assert!(Document::new("<li>a</li>").find("body").is_ok());
+	let table_parts = Document::new_fragment("<tr><td>1</td></tr>");
+	assert_eq!(table_parts.html(), "<html>1</html>");
+	assert_eq!(table_parts.find("td").unwrap_err().to_string(), "not found 'td'");
+	Ok(())
+}
@@ -0,0 +1,227 @@
+use debris::{Document, DocumentBuilder, ErrorReport, Find, Node, Operation, ReasonKind, Result, ResultExt};
+use std::num::ParseIntError;
+
+#[cfg(feature = "test-helpers")]
+#[test]
+fn expect_find_panics_with_breadcrumb_and_snapshot() {
+	let document = Document::new("<ul><li>a</li></ul>");
+	let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+		document.expect_find("ul").expect_find(".missing");
+	}))
+	.unwrap_err();
+	let message = panic.downcast_ref::<String>().unwrap();
+	assert!(message.contains("not found '.missing' 'ul'"), "{}", message);
+	assert!(message.contains("<ul><li>a</li></ul>"), "{}", message);
+}
+
+#[test]
+fn operation_names_for_failing_chain() {
+	let document = Document::new("<div class=question-summary><div class=votes><span>many</span></div></div>");
+	let err = (|| document.find_all(".question-summary")?.next().unwrap().find(".votes span")?.text().parse::<i64>())().unwrap_err();
+	assert_eq!(err.operation_names(), ["find_all:.question-summary", "find:.votes span", "text", "parse:i64"]);
+}
+
+#[test]
+fn extract_reroots_breadcrumb_at_node() {
+	fn helper(node: &Node) -> Result<()> {
+		node.find("p")?.find(".missing")?;
+		Ok(())
+	}
+	let document = Document::new("<div><p>x</p></div>");
+	let body = document.find("body").unwrap();
+	let div = body.find("div").unwrap();
+	let plain = helper(&div).unwrap_err();
+	assert_eq!(plain.operation_names(), ["find:body", "find:div", "find:p", "find:.missing"]);
+	assert_eq!(plain.snapshots.len(), 4);
+	let extracted = div.extract(helper).unwrap_err();
+	assert_eq!(extracted.operation_names(), ["find:div", "find:p", "find:.missing"]);
+	assert_eq!(extracted.snapshots, &plain.snapshots[2..]);
+}
+
+#[test]
+fn extract_passes_foreign_errors_through() {
+	let document = Document::new("<div><p>x</p></div>");
+	let other = Document::new("<div><span>y</span></div>");
+	let foreign = || -> Result<()> {
+		other.find("body")?.find("div")?.find(".missing")?;
+		Ok(())
+	};
+	let direct = foreign().unwrap_err();
+	let body = document.find("body").unwrap();
+	let div = body.find("div").unwrap();
+	let passed = div.extract(|_| foreign()).unwrap_err();
+	assert_eq!(passed.operation_names(), direct.operation_names());
+	assert_eq!(passed.snapshots, direct.snapshots);
+	assert_eq!(passed.snapshots.len(), 3);
+}
+
+#[test]
+fn pretty_debug_shows_innermost_snapshot() {
+	let document = Document::new("<ul><li class=item>a long item text</li></ul>");
+	let err = document.find(".item").unwrap().find("b").unwrap_err();
+	let pretty = format!("{:#?}", err);
+	assert!(pretty.contains("<li class=\"item\">a long item text</li>"), "{}", pretty);
+	let truncated = format!("{:#.10?}", err);
+	assert!(truncated.contains("<li class=...") && !truncated.contains("item text"), "{}", truncated);
+}
+
+#[test]
+fn cloned_external_error_displays_the_same() {
+	let document = Document::new("<p>many</p>");
+	let err = document.find("p").unwrap().text().parse::<u32>().unwrap_err();
+	let clone = err.clone();
+	assert_eq!(clone.to_string(), err.to_string());
+	assert_eq!(clone.reason_kind(), ReasonKind::External);
+	assert!(clone.downcast_ref::<ParseIntError>().is_some());
+	assert!(clone.backtrace.is_none());
+}
+
+#[test]
+fn clone_shallow_keeps_external_reason() {
+	let document = Document::new("<p>many</p>");
+	let err = document.find("p").unwrap().text().parse::<u32>().unwrap_err();
+	let copy = err.clone_shallow();
+	assert_eq!(copy.to_string(), err.to_string());
+	assert_eq!(copy.to_string(), "invalid digit found in string parse as u32 text 'p'");
+	assert_eq!(copy.reason_kind(), ReasonKind::External);
+	assert!(copy.downcast_ref::<ParseIntError>().is_some());
+	assert_eq!(copy.snapshots, err.snapshots);
+	assert!(copy.backtrace.is_none());
+}
+
+#[test]
+fn innermost_snapshot_is_the_failing_row() {
+	let document = Document::new("<table><tr><td><b>1</b></td></tr><tr><td>x</td></tr></table>");
+	let err = (|| -> Result<Vec<String>> { document.find_all("tr")?.map(|row| Ok(row.find("b")?.text().string())).collect() })().unwrap_err();
+	assert_eq!(err.innermost_snapshot(), Some("<tr><td>x</td></tr>"));
+	assert_eq!(err.snapshots.len(), 2);
+}
+
+#[test]
+fn error_report_groups_by_breadcrumb() {
+	let document = Document::new("<div class=q><span class=votes>many</span></div><div class=q><span class=votes>-</span></div><div class=q></div>");
+	let mut report = ErrorReport::new();
+	for question in document.find_all(".q").unwrap() {
+		match question.find(".votes") {
+			Ok(votes) => report.push(votes.text().parse::<u32>().unwrap_err()),
+			Err(error) => report.push(error),
+		}
+	}
+	report.push(document.find("h1").unwrap_err());
+	let summary = report.summarize();
+	assert_eq!(summary.len(), 3);
+	assert_eq!(summary[0], (vec!["find_all:.q", "find:.votes", "text", "parse:u32"], 2));
+	assert_eq!(summary[1], (vec!["find_all:.q", "find:.votes"], 1));
+	assert_eq!(summary[2], (vec!["find:h1"], 1));
+}
+
+#[test]
+fn record_of_nested_error() -> Result<()> {
+	let document = DocumentBuilder::new().capture_backtrace(false).parse("<ul><li><b>x</b></li></ul>")?;
+	let list = document.find("ul")?;
+	let item = list.find("li")?;
+	let err = item.find("i").unwrap_err();
+	let record = err.to_record();
+	assert_eq!(record.message, "not found 'i' 'li' 'ul'");
+	assert_eq!(record.reason, "not found");
+	assert_eq!(record.operations, ["find:ul", "find:li", "find:i"]);
+	assert_eq!(record.snapshots.iter().map(|snapshot| snapshot.length).collect::<Vec<_>>(), [err.snapshots[0].len(), 26, 17]);
+	assert!(record.snapshots.iter().all(|snapshot| snapshot.hash.len() == 16));
+	assert!(record.backtrace.is_empty());
+	Ok(())
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_record_fields() -> Result<()> {
+	let document = DocumentBuilder::new().capture_backtrace(false).parse("<ul><li><b>x</b></li></ul>")?;
+	let list = document.find("ul")?;
+	let item = list.find("li")?;
+	let err = item.find("i").unwrap_err();
+	let json: serde_json::Value = serde_json::from_str(&err.to_json()).unwrap();
+	let mut fields: Vec<&str> = json.as_object().unwrap().keys().map(String::as_str).collect();
+	fields.sort_unstable();
+	assert_eq!(fields, ["backtrace", "message", "operations", "reason", "snapshots"]);
+	assert_eq!(json["message"].as_str(), Some("not found 'i' 'li' 'ul'"));
+	assert_eq!(json["reason"].as_str(), Some("not found"));
+	let operations: Vec<&str> = json["operations"].as_array().unwrap().iter().map(|name| name.as_str().unwrap()).collect();
+	assert_eq!(operations, ["find:ul", "find:li", "find:i"]);
+	let snapshots = json["snapshots"].as_array().unwrap();
+	assert_eq!(snapshots.len(), 3);
+	assert_eq!(snapshots[2]["length"].as_u64(), Some(17));
+	assert_eq!(snapshots[2]["hash"].as_str(), Some(err.to_record().snapshots[2].hash.as_str()));
+	assert_eq!(json["backtrace"].as_array().map(Vec::len), Some(0));
+	Ok(())
+}
+
+#[test]
+fn save_snapshots_writes_numbered_files() -> Result<()> {
+	let document = Document::new("<ul><li>a</li></ul>");
+	let list = document.find("ul")?;
+	let err = list.find("b").unwrap_err();
+	let dir = std::env::temp_dir().join(format!("debris-test-save-{}", std::process::id()));
+	let paths = err.save_snapshots(&dir.join("nested")).unwrap();
+	assert_eq!(paths, [dir.join("nested").join("0.html"), dir.join("nested").join("1.html")]);
+	assert_eq!(std::fs::read_to_string(&paths[1]).unwrap(), "<ul><li>a</li></ul>");
+	assert_eq!(std::fs::read_to_string(&paths[0]).unwrap(), err.snapshots[0]);
+	let shown = err.display_with_saved_snapshots();
+	let mut lines = shown.lines();
+	assert_eq!(lines.next(), Some("not found 'b' 'ul'"));
+	let saved: Vec<&str> = lines.map(str::trim).collect();
+	assert_eq!(saved.len(), 2);
+	assert_eq!(std::fs::read_to_string(saved[1]).unwrap(), "<ul><li>a</li></ul>");
+	assert_eq!(format!("{:#}", err), err.to_string());
+	std::fs::remove_dir_all(&dir).unwrap();
+	std::fs::remove_dir_all(std::path::Path::new(saved[0]).parent().unwrap()).unwrap();
+	Ok(())
+}
+
+#[test]
+fn backtrace_capture_follows_the_document_setting() -> Result<()> {
+	let mut document = DocumentBuilder::new().capture_backtrace(false).parse("<p>x</p>")?;
+	assert!(document.find("b").unwrap_err().backtrace.is_none());
+	assert!(document.find("p")?.find("b").unwrap_err().backtrace.is_none());
+	document.set_capture_backtrace(true);
+	assert!(document.find("b").unwrap_err().backtrace.is_some());
+	document.set_capture_backtrace(false);
+	assert!(document.find("b").unwrap_err().backtrace.is_none());
+	Ok(())
+}
+
+#[cfg(feature = "anyhow")]
+#[test]
+fn into_anyhow_appends_the_snapshot() {
+	let document = Document::new("<ul><li>a</li></ul>");
+	let err = document.find("ul").unwrap().find("b").unwrap_err().into_anyhow();
+	assert_eq!(err.to_string(), "not found 'b' 'ul'\n\nsnapshot:\n<ul><li>a</li></ul>");
+	assert!(err.downcast_ref::<debris::Error>().is_none());
+}
+
+#[cfg(feature = "eyre")]
+#[test]
+fn into_report_adds_operations_and_snapshot_sections() {
+	let _ = color_eyre::config::HookBuilder::blank().install();
+	let document = Document::new("<ul><li>a</li></ul>");
+	let report = document.find("ul").unwrap().find("b").unwrap_err().into_report();
+	let rendered = format!("{:?}", report);
+	assert!(rendered.contains("not found 'b' 'ul'"), "{}", rendered);
+	assert!(rendered.contains("Operations:") && rendered.contains("'b'"), "{}", rendered);
+	assert!(rendered.contains("Snapshot:") && rendered.contains("<ul><li>a</li></ul>"), "{}", rendered);
+	assert!(report.downcast_ref::<debris::Error>().is_some());
+}
+
+#[test]
+fn result_ext_attaches_node_context_to_foreign_errors() -> Result<()> {
+	let document = Document::new("<ul><li>12a</li></ul>");
+	let li = document.find("li")?;
+	let err = li.text().string().parse::<u32>().context_node(&li).unwrap_err();
+	assert_eq!(err.operation_names(), ["find:li", "external"]);
+	assert_eq!(err.to_string(), "invalid digit found in string external 'li'");
+	assert_eq!(err.innermost_snapshot(), Some("<li>12a</li>"));
+	assert!(err.downcast_ref::<ParseIntError>().is_some());
+	let err = li.text().string().parse::<u32>().with_operation(&li, Operation::Parse { ty: "u32" }).unwrap_err();
+	assert_eq!(err.operation_names(), ["find:li", "parse:u32"]);
+	assert_eq!(err.to_string(), "invalid digit found in string parse as u32 'li'");
+	assert_eq!("12".parse::<u32>().context_node(&li)?, 12);
+	Ok(())
+}
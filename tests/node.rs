@@ -0,0 +1,611 @@
+use debris::{Collection, Document, Find, Node, ReasonKind, Result, Table};
+use std::collections::HashMap;
+
+fn texts(collection: Collection) -> Vec<String> {
+	collection.map(|node| node.text().string()).collect()
+}
+
+/// Text of every position of `table`, with `-` where a row is too short to reach the column.
+fn grid(table: &Table) -> Vec<Vec<String>> {
+	let cell = |row, column| table.cell(row, column).map_or_else(|_| "-".to_owned(), |text| text.string());
+	(0..table.row_count()).map(|row| (0..table.column_count()).map(|column| cell(row, column)).collect()).collect()
+}
+
+#[test]
+fn cell_header_by_column() -> Result<()> {
+	let document = Document::new(
+		"<table><tr><th>Name</th><th>Price</th></tr><tr><td>Apple</td><td>$1</td></tr><tr><td>Pear</td><td>$2</td></tr></table>",
+	);
+	assert_eq!(document.find_nth("td", 0)?.cell_header()?, "Name");
+	assert_eq!(document.find_nth("td", 3)?.cell_header()?, "Price");
+	Ok(())
+}
+
+#[test]
+fn siblings_split_around_anchor() -> Result<()> {
+	let document = Document::new("<ul><li>a</li><li>b</li><li id=anchor>c</li><li>d</li><li>e</li></ul>");
+	let anchor = document.find("#anchor")?;
+	assert_eq!(texts(anchor.siblings_before()), ["a", "b"]);
+	assert_eq!(texts(anchor.siblings_after()), ["d", "e"]);
+	assert_eq!(texts(anchor.siblings()), ["a", "b", "d", "e"]);
+	Ok(())
+}
+
+#[test]
+fn adjacent_bare_text() -> Result<()> {
+	let document = Document::new("<p>Total: <b>Price:</b> $5</p><p><b>a</b><i>b</i></p>");
+	let label = document.find_first("b")?;
+	assert_eq!(label.next_text()?, "$5");
+	assert_eq!(label.prev_text()?, "Total:");
+	let bold = document.find_nth("b", 1)?;
+	assert_eq!(bold.next_text().unwrap_err().reason_kind(), ReasonKind::ExpectedText);
+	assert_eq!(bold.prev_text().unwrap_err().reason_kind(), ReasonKind::NotFound);
+	Ok(())
+}
+
+#[test]
+fn try_text_child_on_mixed_content() -> Result<()> {
+	let document = Document::new("<p>Price: <b>5</b></p>");
+	let p = document.find("p")?;
+	assert_eq!(p.try_text_child(0)?.unwrap(), "Price:");
+	assert!(p.try_text_child(1)?.is_none());
+	assert_eq!(p.try_text_child(2).unwrap_err().reason_kind(), ReasonKind::NotFound);
+	Ok(())
+}
+
+#[test]
+fn normalized_html_ignores_formatting() -> Result<()> {
+	let pretty = Document::new("<div class=x id=a>\n  <p>hello   world</p>\n</div>");
+	let compact = Document::new(r#"<div id="a" class="x"><p>hello world</p></div>"#);
+	let normalized = pretty.find("div")?.normalized_html();
+	assert_eq!(normalized, compact.find("div")?.normalized_html());
+	assert_eq!(normalized, r#"<div class="x" id="a"><p>hello world</p></div>"#);
+	Ok(())
+}
+
+#[test]
+fn ancestors_find_by_tag_and_attribute() -> Result<()> {
+	let document = Document::new("<section data-kind=promo><div data-kind=card><section><p>deal</p></section></div></section>");
+	let p = document.find("p")?;
+	let promo = p.ancestors_find(|node| node.name() == "section" && node.has_attr("data-kind")).unwrap();
+	assert_eq!(promo.attr("data-kind")?, "promo");
+	assert!(p.ancestors_find(|node| node.name() == "article").is_none());
+	Ok(())
+}
+
+#[test]
+fn text_until_stops_at_marker() -> Result<()> {
+	let document = Document::new("<article><p>Body <em>text</em></p><div class=share><p>Share</p></div><p>Footer</p></article>");
+	assert_eq!(document.find("article")?.text_until(".share")?, "Body text");
+	Ok(())
+}
+
+#[test]
+fn count_in_nested_lists() -> Result<()> {
+	let document = Document::new("<div><ul><li>a<ul><li>b</li><li>c</li></ul></li><li>d</li></ul></div>");
+	let div = document.find("div")?;
+	assert_eq!(div.count("li")?, 4);
+	assert_eq!(div.count("ul ul li")?, 2);
+	assert_eq!(div.count("table")?, 0);
+	Ok(())
+}
+
+#[test]
+fn markdown_keeps_inline_formatting() -> Result<()> {
+	let document = Document::new(r#"<p>Buy <b>now</b>, <em>cheap</em> at <a href="/shop">the <span>shop</span></a>.</p>"#);
+	assert_eq!(document.find("p")?.markdown(), "Buy **now**, *cheap* at [the shop](/shop).");
+	Ok(())
+}
+
+#[test]
+fn closest_form_through_fieldsets() -> Result<()> {
+	let document = Document::new(
+		"<form id=signup><fieldset><fieldset><label><input name=email></label></fieldset></fieldset></form><input name=search>",
+	);
+	assert_eq!(document.find("[name=email]")?.closest_form()?.attr("id")?, "signup");
+	assert_eq!(document.find("[name=search]")?.closest_form().unwrap_err().reason_kind(), ReasonKind::NotFound);
+	Ok(())
+}
+
+#[test]
+fn attr_all_splits_tokens() -> Result<()> {
+	let document = Document::new("<a class=\" btn\tbtn-primary\n large \" rel=\"noopener noreferrer\" data-tags=\"a, b,,c\">x</a>");
+	let a = document.find("a")?;
+	assert_eq!(a.attr_all("class", ' '), ["btn", "btn-primary", "large"]);
+	assert_eq!(a.attr_all("rel", ' '), ["noopener", "noreferrer"]);
+	assert_eq!(a.attr_all("data-tags", ','), ["a", "b", "c"]);
+	assert!(a.attr_all("title", ' ').is_empty());
+	Ok(())
+}
+
+#[test]
+fn html_len_matches_outer_html() -> Result<()> {
+	let document = Document::new("<div><p title=\"a &amp; b\">zażółć</p><br></div>");
+	let div = document.find("div")?;
+	assert_eq!(div.html_len(), div.outer_html().len());
+	Ok(())
+}
+
+#[test]
+fn type_relative_indexing() -> Result<()> {
+	let document = Document::new("<div><h2>a</h2><p>one</p><h2>b</h2><p>two</p><p>three</p></div>");
+	let two = document.find_nth("p", 1)?;
+	assert_eq!(two.type_index(), 1);
+	assert_eq!(two.nth_of_type("h2", 1)?.text(), "b");
+	assert_eq!(two.nth_of_type("p", 2)?.text(), "three");
+	assert_eq!(two.nth_of_type("p", 3).unwrap_err().reason_kind(), ReasonKind::NotFound);
+	Ok(())
+}
+
+#[test]
+fn text_equality_exact_and_normalized() -> Result<()> {
+	let document = Document::new("<p>  In   Stock\n </p>");
+	let p = document.find("p")?;
+	assert!(p.text_eq("In   Stock"));
+	assert!(!p.text_eq("In Stock"));
+	assert!(p.text_eq_normalized(" In Stock", false));
+	assert!(!p.text_eq_normalized("in stock", false));
+	assert!(p.text_eq_normalized("in  stock", true));
+	Ok(())
+}
+
+#[test]
+fn descendant_relationships() -> Result<()> {
+	let document = Document::new("<div id=outer><div id=inner><p>x</p></div></div><div id=copy><p>x</p></div>");
+	let outer = document.find("#outer")?;
+	let p = document.find("#inner p")?;
+	assert!(p.is_descendant_of(&outer));
+	assert!(outer.is_ancestor_of(&p));
+	assert!(!outer.is_descendant_of(&p));
+	assert!(!p.is_descendant_of(&p));
+	assert!(!p.is_descendant_of(&document.find("#copy")?));
+	Ok(())
+}
+
+#[test]
+fn closest_within_stops_at_bound() -> Result<()> {
+	let document = Document::new("<div class=card><div><div><p>x</p></div></div></div>");
+	let p = document.find("p")?;
+	assert_eq!(p.closest_within(".card", 2).unwrap_err().reason_kind(), ReasonKind::NotFound);
+	assert!(p.closest_within(".card", 3)?.has_class("card"));
+	assert_eq!(p.closest_within("p", 0)?.text(), "x");
+	Ok(())
+}
+
+#[test]
+fn find_all_within_depth_skips_nested_lists() -> Result<()> {
+	let document = Document::new("<ul id=outer><li>a<ul><li>a1</li><li>a2</li></ul></li><li>b</li></ul>");
+	let outer = document.find("#outer")?;
+	assert_eq!(outer.find_all_within_depth("li", 1)?.count(), 2);
+	assert_eq!(outer.find_all_within_depth("li", 3)?.count(), 4);
+	assert_eq!(outer.find_all_within_depth("li", 0)?.count(), 0);
+	Ok(())
+}
+
+#[test]
+fn prev_heading_across_sections() -> Result<()> {
+	let document = Document::new(
+		"<h1>Title</h1><section><h2>Specs</h2><div><p id=weight>1</p></div></section><section><div><h2>Reviews</h2></div><p id=review></p></section>",
+	);
+	assert_eq!(document.find("#weight")?.prev_heading()?.text(), "Specs");
+	assert_eq!(document.find("#review")?.prev_heading()?.text(), "Reviews");
+	assert_eq!(document.find_first("section")?.prev_heading()?.text(), "Title");
+	assert_eq!(document.find("h1")?.prev_heading().unwrap_err().reason_kind(), ReasonKind::NotFound);
+	Ok(())
+}
+
+#[test]
+fn jquery_text_keeps_whitespace() -> Result<()> {
+	let document = Document::new("<div>\n  <b>Fish</b> &amp;\n  chips<br>\n</div>");
+	let div = document.find("div")?;
+	assert_eq!(div.jquery_text(), "\n  Fish &\n  chips\n");
+	assert_eq!(div.text(), "Fish &\n  chips");
+	Ok(())
+}
+
+#[test]
+fn outer_html_redacted_masks_values() -> Result<()> {
+	let document = Document::new("<form><input value=hunter2><a data-user=42>me</a></form>");
+	let html = document.find("form")?.outer_html_redacted(&["value", "data-*"]);
+	assert_eq!(html, r#"<form><input value="***"><a data-user="***">me</a></form>"#);
+	Ok(())
+}
+
+#[test]
+fn closest_link_around_image() -> Result<()> {
+	let document = Document::new(r#"<a name=top></a><a href="/product/1"><span><img src=a.png></span></a><img src=b.png>"#);
+	assert_eq!(document.find("[src='a.png']")?.closest_link()?, "/product/1");
+	assert_eq!(document.find("[src='b.png']")?.closest_link().unwrap_err().reason_kind(), ReasonKind::NotFound);
+	Ok(())
+}
+
+#[test]
+fn find_all_self_includes_node_first() -> Result<()> {
+	let document = Document::new("<div class=card id=outer><div class=card id=inner></div><p class=card id=p></p></div>");
+	let outer = document.find("#outer")?;
+	let ids: Vec<String> = outer.find_all_self(".card")?.map(|node| node.attr("id").unwrap().string()).collect();
+	assert_eq!(ids, ["outer", "inner", "p"]);
+	assert_eq!(outer.find_all(".card")?.count(), 2);
+	Ok(())
+}
+
+#[test]
+fn owned_node_keeps_table_cell() -> Result<()> {
+	let document = Document::new("<table><tr><td class=price>$<b>5</b></td></tr></table>");
+	let owned = document.find(".price")?.to_owned_node();
+	let (name, text) = std::thread::spawn(move || owned.with(|td| Ok((td.name().to_owned(), td.text().string()))).unwrap()).join().unwrap();
+	assert_eq!((name.as_str(), text.as_str()), ("td", "$5"));
+	Ok(())
+}
+
+#[test]
+fn owned_node_reroots_only_its_own_errors() -> Result<()> {
+	let document = Document::new("<table><tr><td class=price>$5</td></tr></table>");
+	let owned = document.find(".price")?.to_owned_node();
+	let err = owned.with(|td| td.find("i").map(|_| ())).unwrap_err();
+	assert_eq!(err.operation_names(), ["find:.price", "find:i"]);
+	assert!(err.snapshots[0].contains("<table>"));
+	let other = Document::new("<p class=price>$6</p>").find(".price")?.to_owned_node();
+	let foreign = other.with(|p| Ok(p.find("i").unwrap_err()))?;
+	let passed = owned.with(|_| Err::<(), _>(foreign.clone())).unwrap_err();
+	assert_eq!(passed.snapshots, foreign.snapshots);
+	Ok(())
+}
+
+#[test]
+fn table_overlapping_spans() -> Result<()> {
+	let document = Document::new(concat!(
+		"<table><tr><td rowspan=2>A</td><td colspan=2>B</td></tr>",
+		"<tr><td>C</td><td rowspan=2>D</td></tr>",
+		"<tr><td colspan=2>E</td></tr></table>",
+	));
+	let node = document.find("table")?;
+	let table = node.as_table()?;
+	assert!(table.headers().is_empty());
+	assert_eq!(grid(&table), [["A", "B", "B"], ["A", "C", "D"], ["E", "E", "D"]]);
+	assert_eq!(table.cell_node(2, 2)?.text().string(), "D");
+	Ok(())
+}
+
+#[test]
+fn table_rowspan_zero_and_too_long_are_clamped() -> Result<()> {
+	let document = Document::new(concat!(
+		"<table><tr><td rowspan=0>A</td><td>1</td><td rowspan=9>Z</td></tr>",
+		"<tr><td>2</td></tr>",
+		"<tr><td>3</td></tr></table>",
+	));
+	let node = document.find("table")?;
+	let table = node.as_table()?;
+	assert_eq!(table.row_count(), 3);
+	assert_eq!(grid(&table), [["A", "1", "Z"], ["A", "2", "Z"], ["A", "3", "Z"]]);
+	Ok(())
+}
+
+#[test]
+fn table_ragged_rows() -> Result<()> {
+	let document = Document::new("<table><tr><td>a</td><td>b</td><td>c</td></tr><tr><td>d</td></tr><tr></tr></table>");
+	let node = document.find("table")?;
+	let table = node.as_table()?;
+	assert_eq!(table.column_count(), 3);
+	assert_eq!(grid(&table), [vec!["a", "b", "c"], vec!["d", "-", "-"], vec!["-", "-", "-"]]);
+	let err = table.cell(1, 2).unwrap_err();
+	assert_eq!(err.reason_kind(), ReasonKind::NotFound);
+	assert_eq!(err.operation_names(), ["find:table", "table_cell:1:2"]);
+	Ok(())
+}
+
+#[test]
+fn table_header_over_spanned_columns() -> Result<()> {
+	let document = Document::new(concat!(
+		"<table><thead><tr><th rowspan=2>Name</th><th colspan=2>Price</th></tr><tr><th>Net</th><th>Gross</th></tr></thead>",
+		"<tbody><tr><td>Apple</td><td>1</td><td>2</td></tr></tbody></table>",
+		"<table><tr><th colspan=2>Price</th><th>Stock</th></tr><tr><td>1</td><td>2</td><td>3</td></tr></table>",
+	));
+	let first = document.find_first("table")?;
+	let table = first.as_table()?;
+	assert_eq!(table.headers(), ["Name", "Net", "Gross"]);
+	assert_eq!(table.cell_by_header(0, "Gross")?, "2");
+	assert_eq!(table.column("Price").unwrap_err().reason_kind(), ReasonKind::NotFound);
+	let second = document.find_nth("table", 1)?;
+	let table = second.as_table()?;
+	assert_eq!(table.headers(), ["Price", "Price", "Stock"]);
+	assert_eq!(table.column("Stock")?, 2);
+	assert_eq!(table.cell_by_header(0, "Price")?, "1");
+	assert_eq!(document.find_nth("td", 3)?.cell_header()?, "Price");
+	Ok(())
+}
+
+#[test]
+fn form_default_values_and_urlencoding() -> Result<()> {
+	let document = Document::new(concat!(
+		"<form action=/search method=POST>",
+		"<input type=hidden name=token value='a&b=c d/é'>",
+		"<input type=checkbox name=agree checked><input type=checkbox name=news value=yes>",
+		"<input type=radio name=size value=s><input type=radio name=size value=m checked>",
+		"<select name=color><option>red</option><option value=g>green</option></select>",
+		"<select name=sort><option value=a>A</option><option value=b selected>B</option></select>",
+		"<select name=tags multiple><option value=x selected>X</option><option value=y>Y</option><option value=z selected>Z</option></select>",
+		"<select name=none multiple><option value=q>Q</option></select>",
+		"<textarea name=note>hi there</textarea>",
+		"<input name=off value=1 disabled><input type=submit name=go value=Go>",
+		"</form>",
+	));
+	let node = document.find("form")?;
+	let mut form = node.as_form()?;
+	assert_eq!((form.action(), form.method().as_str()), (Some("/search"), "post"));
+	assert_eq!(form.pairs(), [
+		("token", "a&b=c d/é"),
+		("agree", "on"),
+		("size", "m"),
+		("color", "red"),
+		("sort", "b"),
+		("tags", "x"),
+		("tags", "z"),
+		("note", "hi there"),
+	]);
+	assert_eq!(form.urlencoded(), "token=a%26b%3Dc+d%2F%C3%A9&agree=on&size=m&color=red&sort=b&tags=x&tags=z&note=hi+there");
+	assert!(form.fields().iter().any(|field| field.name == "off" && !field.submitted));
+	assert_eq!(form.value("off").unwrap_err().reason_kind(), ReasonKind::NotFound);
+	assert_eq!(form.value("news").unwrap_err().reason_kind(), ReasonKind::NotFound);
+	form.set("size", "l");
+	form.set("page", "2");
+	assert_eq!(form.value("size")?, "l");
+	assert!(form.urlencoded().ends_with("&size=l&color=red&sort=b&tags=x&tags=z&note=hi+there&page=2"));
+	Ok(())
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn datetime_from_attribute_or_text() -> Result<()> {
+	let document = Document::new(concat!(
+		"<time datetime='2024-03-05T14:30:00+02:00'>March 5</time><time datetime=2024-03-05T14:30Z>today</time>",
+		"<time> 2024-03-05 </time><time datetime=soon>2024-03-05</time>",
+	));
+	let datetime = |n| document.find_nth("time", n).and_then(|time| time.datetime()).map(|datetime| datetime.to_string());
+	assert_eq!(datetime(0)?, "2024-03-05 12:30:00");
+	assert_eq!(datetime(1)?, "2024-03-05 14:30:00");
+	assert_eq!(datetime(2)?, "2024-03-05 00:00:00");
+	let err = datetime(3).unwrap_err();
+	assert!(err.to_string().starts_with("\"soon\" isn't a valid date or time datetime "), "{}", err);
+	assert_eq!(err.operation_names(), ["find_nth:time:3", "datetime"]);
+	Ok(())
+}
+
+const REVIEWS: &str = concat!(
+	"<table><tr><td>1</td><td>Accepted</td></tr><tr><td>2</td><td>Rejected</td></tr>",
+	"<tr><td>3</td><td>Rejected</td></tr></table>",
+);
+
+fn status_is(status: &'static str) -> impl Fn(&Node) -> bool {
+	move |row| row.find_nth("td", 1).is_ok_and(|cell| cell.text() == status)
+}
+
+#[test]
+fn find_where_labels_the_predicate_in_errors() -> Result<()> {
+	let document = Document::new(REVIEWS);
+	assert_eq!(document.find_where("tr", "status is Accepted", status_is("Accepted"))?.find_first("td")?.text(), "1");
+	let missing = document.find_where("tr", "status is Pending", status_is("Pending")).unwrap_err();
+	assert_eq!(missing.to_string(), "not found 'tr' where status is Pending");
+	assert_eq!(missing.operation_names(), ["find_where:tr:status is Pending"]);
+	let multiple = document.find_where("tr", "status is Rejected", status_is("Rejected")).unwrap_err();
+	assert_eq!(multiple.to_string(), "found too many 'tr' where status is Rejected");
+	let rejected = document.find_all_where("tr", "status is Rejected", status_is("Rejected"))?.collect_nodes();
+	assert_eq!(rejected.len(), 2);
+	let err = rejected[1].find("b").unwrap_err();
+	assert_eq!(err.operation_names(), ["find_all_where:tr:status is Rejected", "find:b"]);
+	assert!(err.to_string().ends_with(" of 'tr' where status is Rejected"), "{}", err);
+	Ok(())
+}
+
+#[test]
+fn find_having_and_has_emulate_css_has() -> Result<()> {
+	let document = Document::new(concat!(
+		"<div class=card><h2>A</h2><span class=sale>-10%</span></div><div class=card><h2>B</h2></div>",
+		"<div class=card><h2>C</h2><p><span class=sale>-5%</span></p></div>",
+	));
+	let titles = |cards: Collection| cards.map(|card| card.find("h2").unwrap().text().string()).collect::<Vec<_>>();
+	assert_eq!(titles(document.find_having(".card", ".sale")?), ["A", "C"]);
+	assert_eq!(titles(document.find_having(".card", "h2")?), ["A", "B", "C"]);
+	assert!(document.find_having(".card", "h3")?.is_empty());
+	assert!(document.find_having(".sale", ".sale")?.is_empty());
+	assert_eq!(document.find_having(".card", "[").err().unwrap().reason_kind(), ReasonKind::InvalidSelector);
+	let err = document.find_having(".card", ".sale")?.nth(1).unwrap().find("h3").unwrap_err();
+	assert_eq!(err.operation_names(), ["find_having:.card:.sale", "find:h3"]);
+	let cards = document.find_all(".card")?.collect_nodes();
+	assert!(cards[0].has(".sale")?);
+	assert!(!cards[1].has(".sale")?);
+	assert!(cards[2].has("p > .sale")?);
+	assert_eq!(cards[1].has("[").unwrap_err().reason_kind(), ReasonKind::InvalidSelector);
+	Ok(())
+}
+
+#[test]
+fn text_blocks_breaks_only_around_blocks() -> Result<()> {
+	let document = Document::new(concat!(
+		"<div id=d><h2>Title</h2><p>First <span>inline</span> text<br>next line</p>",
+		"<ul><li>one</li><li>two <span>more</span><ol><li>a</li></ol></li></ul>tail</div>",
+	));
+	assert_eq!(document.find("#d")?.text_blocks(), "Title\n\nFirst inline text\nnext line\n\n• one\n• two more\n  1. a\n\ntail");
+	Ok(())
+}
+
+#[test]
+fn attr_parse_and_text_parse_name_the_target_type() -> Result<()> {
+	let document = Document::new("<li data-id=42 data-big=4294967296 data-name=x>7 items</li>");
+	let li = document.find("li")?;
+	assert_eq!(li.attr_parse::<u32>("data-id")?, 42);
+	let invalid = li.attr_parse::<u32>("data-name").unwrap_err();
+	assert_eq!(invalid.operation_names(), ["find:li", "attr_parse:data-name:u32"]);
+	assert_eq!(invalid.to_string(), "invalid digit found in string attr 'data-name' as u32 'li'");
+	let overflow = li.attr_parse::<u32>("data-big").unwrap_err();
+	assert_eq!(overflow.to_string(), "value \"4294967296\" too large for u32 attr 'data-big' as u32 'li'");
+	let missing = li.attr_parse::<u32>("data-missing").unwrap_err();
+	assert_eq!(missing.reason_kind(), ReasonKind::NotFound);
+	assert_eq!(missing.operation_names(), ["find:li", "attr_parse:data-missing:u32"]);
+	let text = li.text_parse::<u8>().unwrap_err();
+	assert_eq!(text.operation_names(), ["find:li", "text_parse:u8"]);
+	assert_eq!(text.to_string(), "invalid digit found in string text as u8 'li'");
+	Ok(())
+}
+
+#[test]
+fn sibling_navigation_and_its_errors() -> Result<()> {
+	let document = Document::new("<dl><dt id=first>Name</dt>\n<dd>Ann</dd><dt id=last>Age</dt></dl>");
+	let first = document.find("#first")?;
+	assert_eq!(first.next_sibling().unwrap_err().reason_kind(), ReasonKind::ExpectedElement);
+	let dd = first.next_sibling_element()?;
+	assert_eq!(dd.text(), "Ann");
+	assert_eq!(dd.prev_sibling().unwrap_err().reason_kind(), ReasonKind::ExpectedElement);
+	assert_eq!(dd.prev_sibling_element()?.text(), "Name");
+	assert_eq!(dd.next_sibling()?.text(), "Age");
+	assert_eq!(first.prev_sibling().unwrap_err().reason_kind(), ReasonKind::NotFound);
+	assert_eq!(first.prev_sibling_element().unwrap_err().reason_kind(), ReasonKind::NotFound);
+	let last = document.find("#last")?;
+	assert_eq!(last.next_sibling_element().unwrap_err().reason_kind(), ReasonKind::NotFound);
+	let err = last.next_sibling().unwrap_err();
+	assert_eq!(err.operation_names(), ["find:#last", "next_sibling"]);
+	assert_eq!(err.to_string(), "not found next sibling '#last'");
+	Ok(())
+}
+
+#[test]
+fn closest_starts_at_the_node_itself() -> Result<()> {
+	let document = Document::new("<article id=post><div class=body><p><em>x</em></p></div></article>");
+	let em = document.find("em")?;
+	assert_eq!(em.closest("em")?.name(), "em");
+	assert_eq!(em.closest("p")?.name(), "p");
+	assert_eq!(em.closest("article")?.attr("id")?, "post");
+	let missing = em.closest("section").unwrap_err();
+	assert_eq!(missing.reason_kind(), ReasonKind::NotFound);
+	assert_eq!(missing.operation_names(), ["find:em", "closest:section"]);
+	assert_eq!(missing.to_string(), "not found closest 'section' 'em'");
+	assert_eq!(em.closest("[").unwrap_err().reason_kind(), ReasonKind::InvalidSelector);
+	assert_eq!(em.closest_within(".body", 2)?.name(), "div");
+	let bounded = em.closest_within(".body", 1).unwrap_err();
+	assert_eq!(bounded.to_string(), "not found closest '.body' within 1 'em'");
+	Ok(())
+}
+
+#[test]
+fn children_elements_skip_text_and_comments() -> Result<()> {
+	let document = Document::new("<ul>\n<li>a</li>\n<!-- b -->\n<li>c</li> loose text <li>d</li></ul>");
+	let ul = document.find("ul")?;
+	assert_eq!(ul.child_count(), 3);
+	assert_eq!(texts(ul.children_elements()), ["a", "c", "d"]);
+	let li = ul.children_elements().nth(1).unwrap();
+	assert_eq!(li.child_count(), 0);
+	assert!(li.children_elements().is_empty());
+	let err = li.find("b").unwrap_err();
+	assert_eq!(err.operation_names(), ["find:ul", "children_elements", "find:b"]);
+	Ok(())
+}
+
+#[test]
+fn attrs_attr_opt_and_has_attr() -> Result<()> {
+	let document = Document::new("<input type=checkbox name=agree disabled value=\"\">");
+	let input = document.find("input")?;
+	let mut attrs: Vec<_> = input.attrs().collect();
+	attrs.sort_unstable();
+	assert_eq!(attrs, [("disabled", ""), ("name", "agree"), ("type", "checkbox"), ("value", "")]);
+	assert!(input.has_attr("disabled"));
+	assert!(!input.has_attr("checked"));
+	assert_eq!(input.attr_opt("name").unwrap(), "agree");
+	assert_eq!(input.attr_opt("value").unwrap(), "");
+	assert!(input.attr_opt("checked").is_none());
+	let err = input.attr("checked").unwrap_err();
+	assert_eq!(err.reason_kind(), ReasonKind::NotFound);
+	assert_eq!(err.to_string(), "not found attr 'checked' 'input'");
+	Ok(())
+}
+
+#[test]
+fn classes_and_require_class() -> Result<()> {
+	let document = Document::new("<ul><li class=\" answer\taccepted  top \">x</li></ul>");
+	let li = document.find("li")?;
+	let mut classes: Vec<_> = li.classes().collect();
+	classes.sort_unstable();
+	assert_eq!(classes, ["accepted", "answer", "top"]);
+	assert!(li.has_class("accepted"));
+	assert!(!li.has_class("accept"));
+	li.require_class("accepted")?;
+	let err = li.require_class("rejected").unwrap_err();
+	assert_eq!(err.reason_kind(), ReasonKind::MissingClass);
+	assert_eq!(err.operation_names(), ["find:li", "require_class:rejected"]);
+	assert_eq!(err.to_string(), "missing class 'rejected' class 'rejected' 'li'");
+	Ok(())
+}
+
+#[test]
+fn data_and_dataset_read_data_attributes() -> Result<()> {
+	let document = Document::new("<div id=user data-user-id=7 data-role=admin title=x>Ann</div>");
+	let div = document.find("#user")?;
+	assert_eq!(div.data("user-id")?, "7");
+	assert_eq!(div.data("user-id")?.parse::<u32>()?, 7);
+	let err = div.data("missing").unwrap_err();
+	assert_eq!(err.reason_kind(), ReasonKind::NotFound);
+	assert_eq!(err.operation_names(), ["find:#user", "attr:data-missing"]);
+	assert_eq!(div.dataset(), vec![("user-id", "7"), ("role", "admin")].into_iter().collect::<HashMap<_, _>>());
+	assert!(document.find("body")?.dataset().is_empty());
+	Ok(())
+}
+
+#[test]
+fn find_opt_distinguishes_missing_from_ambiguous() -> Result<()> {
+	let document = Document::new("<div class=post><span class=badge>new</span><a>1</a><a>2</a></div>");
+	let post = document.find(".post")?;
+	assert_eq!(post.find_opt(".badge")?.unwrap().text(), "new");
+	assert!(post.find_opt(".pinned")?.is_none());
+	let multiple = post.find_opt("a").unwrap_err();
+	assert_eq!(multiple.reason_kind(), ReasonKind::MultipleFound);
+	assert_eq!(multiple.to_string(), "found too many 'a' '.post'");
+	assert_eq!(post.find_opt("[").unwrap_err().reason_kind(), ReasonKind::InvalidSelector);
+	let err = post.find_opt(".badge")?.unwrap().find("b").unwrap_err();
+	assert_eq!(err.operation_names(), ["find:.post", "find:.badge", "find:b"]);
+	Ok(())
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_from_script_elements_only() -> Result<()> {
+	let document = Document::new(concat!(
+		r#"<script id=state type="application/json; charset=utf-8">{"user": {"id": 7}}</script>"#,
+		r#"<script id=broken type=application/json>{"user":</script><script id=code>var user;</script><div id=div>{}</div>"#,
+	));
+	let state: serde_json::Value = document.find("#state")?.json()?;
+	assert_eq!(state["user"]["id"].as_u64(), Some(7));
+	let broken = document.find("#broken")?.json::<serde_json::Value>().unwrap_err();
+	assert_eq!(broken.reason_kind(), ReasonKind::External);
+	assert_eq!(broken.operation_names(), ["find:#broken", "json"]);
+	assert!(broken.to_string().ends_with(" JSON '#broken'"), "{}", broken);
+	for id in ["#code", "#div"].iter() {
+		let err = document.find(*id)?.json::<serde_json::Value>().unwrap_err();
+		assert_eq!(err.reason_kind(), ReasonKind::Logic);
+		assert!(err.to_string().starts_with("expected a JSON <script> JSON"), "{}", err);
+	}
+	Ok(())
+}
+
+#[test]
+fn collection_adapters_keep_row_context() -> Result<()> {
+	let document = Document::new("<ul><li><b>1</b></li><li>x</li><li><b>z</b></li></ul>");
+	assert_eq!(document.find_all("li > b")?.nth(0)?.text().parse::<u32>()?, 1);
+	let err = document.find_all("li")?.map_collect(|li| li.find("b")?.text().parse::<u32>()).unwrap_err();
+	assert_eq!(err.operation_names(), ["find_all:li", "find:b"]);
+	assert_eq!(err.innermost_snapshot(), Some("<li>x</li>"));
+	let bold = document.find_all("li")?.try_filter_map(|li| Ok(li.find_opt("b")?.map(|b| b.text().string())))?;
+	assert_eq!(bold, ["1", "z"]);
+	let err = document.find_all("li")?.try_filter_map(|li| li.find_opt("b")?.map(|b| b.text().parse::<u32>()).transpose()).unwrap_err();
+	assert_eq!(err.operation_names(), ["find_all:li", "find:b", "text", "parse:u32"]);
+	assert!(err.snapshots.iter().any(|snapshot| snapshot == "<li><b>z</b></li>"), "{:?}", err.snapshots);
+	assert_eq!(document.find_all("li")?.first()?.text(), "1");
+	assert_eq!(document.find_all("li")?.last()?.text(), "z");
+	let mut rest = document.find_all("li")?;
+	rest.next();
+	assert_eq!(rest.first()?.text(), "x");
+	let past_end = document.find_all("li")?.nth(3).err().unwrap();
+	assert_eq!(past_end.reason_kind(), ReasonKind::NotFound);
+	assert_eq!(past_end.operation_names(), ["find_all:li"]);
+	assert_eq!(document.find_all("p")?.first().err().unwrap().reason_kind(), ReasonKind::NotFound);
+	assert_eq!(document.find_all("p")?.last().err().unwrap().operation_names(), ["find_all:p"]);
+	Ok(())
+}
@@ -0,0 +1,11 @@
+use debris::{Document, Find, Result, SelectorBuilder};
+
+#[test]
+fn builder_escapes_quoted_values() -> Result<()> {
+	let document = Document::new(r#"<a title='say "hi"'>quoted</a><a title="say">plain</a><a title='say "hi"]'>bracket</a>"#);
+	let selector = SelectorBuilder::new().tag("a").attr_eq("title", r#"say "hi""#);
+	assert_eq!(document.find(selector)?.text(), "quoted");
+	let selector = SelectorBuilder::new().attr_eq("title", r#"say "hi"]"#);
+	assert_eq!(document.find(selector)?.text(), "bracket");
+	Ok(())
+}
@@ -0,0 +1,183 @@
+use debris::{Document, DocumentBuilder, Find, NumberFormat, Text, TextOptions, TrimPolicy};
+use std::num::{IntErrorKind, ParseIntError};
+
+/// Runs `f` on the text of a `<p>` containing `value`.
+fn with_text<T>(value: &str, f: impl FnOnce(&Text) -> T) -> T {
+	let document = Document::new(&format!("<p>{}</p>", value));
+	let p = document.find("p").unwrap();
+	f(&p.text())
+}
+
+#[cfg(feature = "unicode")]
+#[test]
+fn full_width_digits_parse_after_nfkc() {
+	with_text("１２３", |text| {
+		assert!(text.parse::<u32>().is_err());
+		assert_eq!(text.normalized_nfkc(), "123");
+		assert_eq!(text.normalized_nfkc().parse::<u32>().unwrap(), 123);
+	});
+}
+
+#[test]
+fn percentage() {
+	assert_eq!(with_text("87%", |text| text.parse_percentage()).unwrap(), 0.87);
+	assert_eq!(with_text("87", |text| text.parse_percentage()).unwrap_err().to_string(), "expected '%' parse as f64 text 'p'");
+	assert_eq!(with_text("abc%", |text| text.parse_percentage()).unwrap_err().to_string(), "invalid float literal parse as f64 text 'p'");
+	let document = DocumentBuilder::new().trim_policy(TrimPolicy::Keep).parse("<p>87% </p><p>\n 4.5/5\n</p><p> 52.1,21.0\t</p>").unwrap();
+	let p = document.find_all("p").unwrap().collect_nodes();
+	assert_eq!(p[0].text().parse_percentage().unwrap(), 0.87);
+	assert_eq!(p[1].text().parse_ratio().unwrap(), (4.5, 5.));
+	assert_eq!(p[2].text().parse_coords().unwrap(), (52.1, 21.));
+}
+
+#[test]
+fn ratio() {
+	assert_eq!(with_text("4.5/5", |text| text.parse_ratio()).unwrap(), (4.5, 5.));
+	assert_eq!(with_text(" 4.5 / 5 ", |text| text.parse_ratio()).unwrap(), (4.5, 5.));
+	assert!(with_text("4.5", |text| text.parse_ratio()).is_err());
+	assert!(with_text("4.5/five", |text| text.parse_ratio()).is_err());
+}
+
+#[test]
+fn parse_or_falls_back_to_default() {
+	assert_eq!(with_text("42", |text| text.parse_or(0u32)), 42);
+	assert_eq!(with_text("", |text| text.parse_or(0u32)), 0);
+	assert_eq!(with_text("4x2", |text| text.parse_or(7u32)), 7);
+}
+
+#[test]
+fn integer_overflow_reports_input() {
+	let err = with_text("300", |text| text.parse::<u8>()).unwrap_err();
+	assert_eq!(err.to_string(), "value \"300\" too large for u8 parse as u8 text 'p'");
+	let err = with_text("-129", |text| text.parse::<i8>()).unwrap_err();
+	assert_eq!(err.to_string(), "value \"-129\" too small for i8 parse as i8 text 'p'");
+	assert_eq!(err.downcast_ref::<ParseIntError>().map(ParseIntError::kind), Some(&IntErrorKind::NegOverflow));
+}
+
+#[cfg(feature = "duration")]
+#[test]
+fn duration_formats() {
+	use std::time::Duration;
+	assert_eq!(with_text("2h", |text| text.parse_duration()).unwrap(), Duration::from_secs(2 * 60 * 60));
+	assert_eq!(with_text("15 min", |text| text.parse_duration()).unwrap(), Duration::from_secs(15 * 60));
+	assert_eq!(with_text("3 Days ago", |text| text.parse_duration()).unwrap(), Duration::from_secs(3 * 24 * 60 * 60));
+	let err = with_text("a fortnight", |text| text.parse_duration()).unwrap_err();
+	assert!(err.to_string().starts_with("unsupported duration format parse as "), "{}", err);
+}
+
+#[test]
+fn key_value_pairs() {
+	let owned = |pair: Option<(&str, &str)>| pair.map(|(key, value)| (key.to_owned(), value.to_owned()));
+	assert_eq!(with_text("Weight : 12 kg", |text| owned(text.split_once(':'))), Some(("Weight".to_owned(), "12 kg".to_owned())));
+	assert_eq!(with_text("Stock 12", |text| owned(text.split_once(':'))), None);
+	assert_eq!(with_text("Stock: 12", |text| text.parse_kv::<u32>(':')).unwrap(), ("Stock".to_owned(), 12));
+	assert_eq!(with_text("Stock 12", |text| text.parse_kv::<u32>(':')).unwrap_err().to_string(), "expected ':' parse as u32 text 'p'");
+}
+
+#[test]
+fn coordinates() {
+	assert_eq!(with_text("37.7749, -122.4194", |text| text.parse_coords()).unwrap(), (37.7749, -122.4194));
+	assert!(with_text("37.7749", |text| text.parse_coords()).is_err());
+	assert!(with_text("37.7749,", |text| text.parse_coords()).is_err());
+}
+
+#[test]
+fn slug_from_title() {
+	assert_eq!(with_text("Hello,  World! -- 2024_edition", |text| text.slug()), "hello-world-2024-edition");
+	#[cfg(feature = "unicode")]
+	assert_eq!(with_text("Café Zażółć", |text| text.slug()), "cafe-zazołc");
+}
+
+#[cfg(feature = "money")]
+#[test]
+fn money_in_usd_and_eur_formats() {
+	assert_eq!(with_text("$1,299.00", |text| text.parse_money()).unwrap(), (1299., Some("$".to_owned())));
+	assert_eq!(with_text("€ 49,90", |text| text.parse_money()).unwrap(), (49.9, Some("€".to_owned())));
+	assert_eq!(with_text("1.299,50 EUR", |text| text.parse_money()).unwrap(), (1299.5, Some("EUR".to_owned())));
+	assert!(with_text("free", |text| text.parse_money()).is_err());
+}
+
+#[test]
+fn ensure_nonempty_field() -> debris::Result<()> {
+	let document = Document::new("<p class=price> 12 </p><p class=stock> \n </p>");
+	let price = document.find(".price")?;
+	assert_eq!(price.text().ensure_nonempty()?, "12");
+	let stock = document.find(".stock")?;
+	assert_eq!(stock.text().ensure_nonempty().unwrap_err().to_string(), "empty text non-empty check text '.stock'");
+	Ok(())
+}
+
+#[test]
+fn parse_number_grouping_and_decimal_separators() {
+	assert_eq!(with_text("1,234.5 pts", |text| text.parse_number::<f64>()).unwrap(), 1234.5);
+	assert_eq!(with_text("Score: -3", |text| text.parse_number::<i32>()).unwrap(), -3);
+	let european = NumberFormat::new().decimal_separator(',').grouping_separators(&['.', ' ']);
+	assert_eq!(with_text("1.234,5", |text| text.parse_number_with::<f64>(&european)).unwrap(), 1234.5);
+	assert_eq!(with_text("1.234,5", |text| text.parse_number::<f64>()).unwrap(), 1.234);
+	let decimal_comma = NumberFormat::new().decimal_separator(',');
+	assert_eq!(with_text("\u{2212}1\u{a0}234,5 €", |text| text.parse_number_with::<f64>(&decimal_comma)).unwrap(), -1234.5);
+}
+
+#[test]
+fn parse_number_malformed_and_overflowing() {
+	assert_eq!(with_text("1,,234", |text| text.parse_number::<u32>()).unwrap(), 1);
+	assert_eq!(with_text("12. Place", |text| text.parse_number::<u32>()).unwrap(), 12);
+	assert_eq!(with_text("pts", |text| text.parse_number::<u32>()).unwrap_err().to_string(), "expected number parse as u32 text 'p'");
+	let err = with_text("99,999,999,999 views", |text| text.parse_number::<u32>()).unwrap_err();
+	assert_eq!(err.to_string(), "value \"99999999999\" too large for u32 parse as u32 text 'p'");
+	assert_eq!(err.downcast_ref::<ParseIntError>().map(ParseIntError::kind), Some(&IntErrorKind::PosOverflow));
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn parse_datetime_with_format() {
+	let parse = |value: &str, format: &str| with_text(value, |text| text.parse_datetime(format).map(|datetime| datetime.to_string()));
+	assert_eq!(parse("5 Mar 2024, 14:30", "%d %b %Y, %H:%M").unwrap(), "2024-03-05 14:30:00");
+	assert_eq!(parse(" 2024-03-05 ", "%Y-%m-%d").unwrap(), "2024-03-05 00:00:00");
+	let err = parse("March 5", "%Y-%m-%d").unwrap_err();
+	assert!(err.to_string().ends_with(" parse as datetime '%Y-%m-%d' text 'p'"), "{}", err);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn parse_datetime_any_tries_formats_in_order() {
+	let parse = |value: &str, formats: &[&str]| with_text(value, |text| text.parse_datetime_any(formats).map(|datetime| datetime.to_string()));
+	assert_eq!(parse("05/03/2024", &["%d/%m/%Y", "%m/%d/%Y"]).unwrap(), "2024-03-05 00:00:00");
+	assert_eq!(parse("05/03/2024", &["%m/%d/%Y", "%d/%m/%Y"]).unwrap(), "2024-05-03 00:00:00");
+	assert_eq!(parse("2024-03-05 14:30", &["%d/%m/%Y", "%Y-%m-%d %H:%M"]).unwrap(), "2024-03-05 14:30:00");
+	let err = parse("yesterday", &["%d/%m/%Y", "%Y-%m-%d"]).unwrap_err();
+	assert_eq!(err.to_string(), "\"yesterday\" doesn't match any format parse as datetime '%d/%m/%Y', '%Y-%m-%d' text 'p'");
+}
+
+#[test]
+fn normalized_with_option_combinations() -> debris::Result<()> {
+	let document = DocumentBuilder::new().trim_policy(TrimPolicy::Keep).parse("<p>  12\u{a0}kg \n\n  per\tbox  </p>")?;
+	let p = document.find("p")?;
+	let text = p.text();
+	let normalized = |options: TextOptions| text.normalized_with(&options).string();
+	assert_eq!(text.normalized(), "12 kg per box");
+	assert_eq!(normalized(TextOptions::new()), "12 kg per box");
+	assert_eq!(normalized(TextOptions::new().keep_newlines(true)), "12 kg\nper box");
+	assert_eq!(normalized(TextOptions::new().keep_nbsp(true)), "12\u{a0}kg per box");
+	assert_eq!(normalized(TextOptions::new().trim(false)), " 12 kg per box ");
+	assert_eq!(normalized(TextOptions::new().trim(false).keep_newlines(true).keep_nbsp(true)), " 12\u{a0}kg\nper box ");
+	Ok(())
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn regex_captures_and_failures() {
+	with_text("42 answers, 3 votes", |text| {
+		let captures = text.regex(r"(\d+) answers, (?P<votes>\d+)").unwrap();
+		assert_eq!(&captures[1], "42");
+		assert_eq!(captures.name("votes").unwrap().as_str(), "3");
+		assert_eq!(text.regex_parse::<u32>(r"(\d+) votes").unwrap(), 3);
+		assert_eq!(text.regex_parse::<u32>(r"\d+").unwrap(), 42);
+		let no_match = text.regex(r"\d+ views").unwrap_err();
+		assert_eq!(no_match.to_string(), r#""42 answers, 3 votes" doesn't match regex '\d+ views' text 'p'"#);
+		assert_eq!(no_match.operation_names(), ["find:p", "text", r"regex:\d+ views"]);
+		let invalid = text.regex("(").unwrap_err();
+		assert_eq!(invalid.reason_kind(), debris::ReasonKind::External);
+		assert_eq!(invalid.operation_names(), ["find:p", "text", "regex:("]);
+	});
+}